@@ -0,0 +1,342 @@
+// Farm accounting shared by the on-chain program and off-chain tools. Nothing in here reads the
+// clock or touches accounts, every operation takes the timestamp it happens at.
+#![no_std]
+
+use core::cmp;
+
+pub const MAX_FEE: u64 = 1_000_000_000;
+pub const ONE_REWARD: u64 = 1_000_000_000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AccountingError {
+    Overflow,
+    InsufficientStake,
+}
+
+pub type Result<T> = core::result::Result<T, AccountingError>;
+
+// The parts of a crop that rewards and fees are accounted in
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Pool {
+    pub total_deposited: u64,
+    // The owner's part of the fees
+    pub fees: u64,
+    pub rewards_per_share: u64,
+    pub reward_rate: u64,
+    pub end_timestamp: u64,
+    pub previous_reward_timestamp: u64,
+    // Part of every fee that goes to whoever is staked when it is charged
+    pub fee_share: u64,
+    pub fees_per_share: u64,
+    // Shared fees not paid out yet, including rounding dust nobody can claim
+    pub staker_fees: u64,
+}
+
+// The parts of a plot that rewards are accounted in
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Position {
+    pub amount: u64,
+    pub debt: u64,
+    pub unclaimed: u64,
+    pub fee_debt: u64,
+    pub unclaimed_fees: u64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Deposit {
+    // Added to the position
+    pub amount: u64,
+    pub fee: u64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Withdrawal {
+    // Paid out of the treasury
+    pub amount: u64,
+    pub fee: u64,
+}
+
+fn add(a: u64, b: u64) -> Result<u64> {
+    a.checked_add(b).ok_or(AccountingError::Overflow)
+}
+
+fn sub(a: u64, b: u64) -> Result<u64> {
+    a.checked_sub(b).ok_or(AccountingError::Overflow)
+}
+
+// a * b / c, with the product taken in 128 bits
+fn mul_div(a: u64, b: u64, c: u64) -> Result<u64> {
+    let result = (a as u128)
+        .checked_mul(b as u128)
+        .and_then(|x| x.checked_div(c as u128))
+        .ok_or(AccountingError::Overflow)?;
+    if result > u64::MAX as u128 {
+        return Err(AccountingError::Overflow);
+    }
+    Ok(result as u64)
+}
+
+// a * b / c rounded up
+fn mul_div_ceil(a: u64, b: u64, c: u64) -> Result<u64> {
+    let result = (a as u128)
+        .checked_mul(b as u128)
+        .and_then(|x| x.checked_add(c as u128 - 1))
+        .and_then(|x| x.checked_div(c as u128))
+        .ok_or(AccountingError::Overflow)?;
+    if result > u64::MAX as u128 {
+        return Err(AccountingError::Overflow);
+    }
+    Ok(result as u64)
+}
+
+pub fn calculate_fee(fee: u64, amount: u64) -> Result<u64> {
+    mul_div(amount, fee, MAX_FEE)
+}
+
+// What a stake of this size has accrued since rewards per share was zero, rounded down
+pub fn calculate_accrued_rewards(rewards_per_share: u64, amount: u64) -> Result<u64> {
+    mul_div(amount, rewards_per_share, ONE_REWARD)
+}
+
+// Rounded up so that the rounding of every plot's rewards goes against the plot. Rounding down
+// both sides would let each settlement gain a unit, and over enough settlements the plots would
+// be owed more than the crop ever emitted.
+pub fn calculate_reward_debt(rewards_per_share: u64, amount: u64) -> Result<u64> {
+    mul_div_ceil(amount, rewards_per_share, ONE_REWARD)
+}
+
+pub fn calculate_additional_rewards(
+    seconds: u64,
+    rewards_per_second: u64,
+    total_deposited: u64
+) -> Result<u64> {
+    let rewards = (seconds as u128)
+        .checked_mul(rewards_per_second as u128)
+        .ok_or(AccountingError::Overflow)?;
+    let result = rewards
+        .checked_mul(ONE_REWARD as u128)
+        .and_then(|x| x.checked_div(total_deposited as u128))
+        .ok_or(AccountingError::Overflow)?;
+    if result > u64::MAX as u128 {
+        return Err(AccountingError::Overflow);
+    }
+    Ok(result as u64)
+}
+
+// Extra withdraw fee that falls linearly from its maximum right after a deposit to zero once the
+// deposit is a period old
+pub fn calculate_penalty_fee(penalty_max: u64, penalty_period: u64, age: u64) -> Result<u64> {
+    if age >= penalty_period {
+        return Ok(0);
+    }
+    mul_div(penalty_max, penalty_period - age, penalty_period)
+}
+
+// The deposit timestamp of a position after adding to it, averaged by amount
+pub fn weighted_deposit_timestamp(
+    amount: u64,
+    deposit_timestamp: u64,
+    added: u64,
+    timestamp: u64
+) -> Result<u64> {
+    let total = add(amount, added)?;
+    if total == 0 {
+        return Ok(timestamp);
+    }
+    let weighted = (amount as u128 * deposit_timestamp as u128)
+        .checked_add(added as u128 * timestamp as u128)
+        .ok_or(AccountingError::Overflow)?;
+    Ok((weighted / total as u128) as u64)
+}
+
+// A crop's share of an emission rate split by allocation points. Rounded down so the shares
+// never add up to more than the whole.
+pub fn apportion_rate(emission_rate: u64, alloc_points: u64, total_alloc_points: u64) -> Result<u64> {
+    if total_alloc_points == 0 {
+        return Ok(0);
+    }
+    mul_div(emission_rate, alloc_points, total_alloc_points)
+}
+
+// What can be paid out of rewards without going over a budget
+pub fn draw_budget(reward_budget: u64, rewards_paid: u64, rewards: u64) -> u64 {
+    cmp::min(rewards, reward_budget.saturating_sub(rewards_paid))
+}
+
+// Splits harvested rewards into what the farmer receives and their referrer's kickback
+pub fn split_referral(referral_fee: u64, rewards: u64) -> Result<(u64, u64)> {
+    let kickback = calculate_fee(referral_fee, rewards)?;
+    Ok((rewards - kickback, kickback))
+}
+
+impl Pool {
+
+    // Distributes the rewards emitted since the previous update, never past the end timestamp
+    pub fn accrue(&mut self, timestamp: u64) -> Result<()> {
+        if timestamp > self.previous_reward_timestamp {
+            let reward_timestamp = cmp::min(timestamp, self.end_timestamp);
+            let time_elapsed = reward_timestamp.saturating_sub(self.previous_reward_timestamp);
+
+            // Only update the rewards if there are at least some tokens deposited
+            if self.total_deposited > 0 {
+                self.rewards_per_share = add(
+                    self.rewards_per_share,
+                    calculate_additional_rewards(time_elapsed, self.reward_rate, self.total_deposited)?
+                )?;
+            }
+
+            self.previous_reward_timestamp = cmp::max(reward_timestamp, self.previous_reward_timestamp);
+        }
+        Ok(())
+    }
+
+    // Starts a period of duration seconds emitting amount plus whatever the running period has
+    // yet to emit, like notifyRewardAmount in Synthetix' StakingRewards. The rate is rounded down,
+    // so the period never emits more than it was funded with.
+    pub fn extend_rewards(&mut self, amount: u64, duration: u64, timestamp: u64) -> Result<()> {
+        self.accrue(timestamp)?;
+        let leftover = match self.end_timestamp.checked_sub(timestamp) {
+            Some(remaining) => mul_div(remaining, self.reward_rate, 1)?,
+            None => 0,
+        };
+        self.reward_rate = mul_div(add(amount, leftover)?, 1, duration)?;
+        self.end_timestamp = add(timestamp, duration)?;
+        Ok(())
+    }
+
+    pub fn projected(&self, timestamp: u64) -> Result<Pool> {
+        let mut pool = *self;
+        pool.accrue(timestamp)?;
+        Ok(pool)
+    }
+
+    // Splits a fee between the owner and everyone staked right now. With nobody staked, or a
+    // share too small to move fees per share, the owner keeps all of it.
+    pub fn charge_fee(&mut self, fee: u64) -> Result<()> {
+        let mut shared = 0;
+        if self.total_deposited > 0 {
+            shared = calculate_fee(self.fee_share, fee)?;
+            let additional_fees = mul_div(shared, ONE_REWARD, self.total_deposited)?;
+            if additional_fees == 0 {
+                shared = 0;
+            }
+            self.fees_per_share = add(self.fees_per_share, additional_fees)?;
+            self.staker_fees = add(self.staker_fees, shared)?;
+        }
+        self.fees = add(self.fees, fee - shared)?;
+        Ok(())
+    }
+
+}
+
+impl Position {
+
+    // Debt is rounded up, so right after a settlement it can sit one unit above what is accrued
+    pub fn pending(&self, pool: &Pool) -> Result<u64> {
+        Ok(calculate_accrued_rewards(pool.rewards_per_share, self.amount)?.saturating_sub(self.debt))
+    }
+
+    pub fn pending_fees(&self, pool: &Pool) -> Result<u64> {
+        Ok(calculate_accrued_rewards(pool.fees_per_share, self.amount)?.saturating_sub(self.fee_debt))
+    }
+
+    // Moves pending rewards and fees into the unclaimed balances. The pool must be accrued first.
+    pub fn settle(&mut self, pool: &Pool) -> Result<()> {
+        self.unclaimed = add(self.unclaimed, self.pending(pool)?)?;
+        self.unclaimed_fees = add(self.unclaimed_fees, self.pending_fees(pool)?)?;
+        self.reset_debt(pool)
+    }
+
+    // After the amount changes, so that nothing accrued before counts for the new amount
+    fn reset_debt(&mut self, pool: &Pool) -> Result<()> {
+        self.debt = calculate_reward_debt(pool.rewards_per_share, self.amount)?;
+        self.fee_debt = calculate_reward_debt(pool.fees_per_share, self.amount)?;
+        Ok(())
+    }
+
+}
+
+pub fn deposit(
+    pool: &mut Pool,
+    position: &mut Position,
+    amount: u64,
+    deposit_fee: u64,
+    timestamp: u64
+) -> Result<Deposit> {
+    let fee = calculate_fee(deposit_fee, amount)?;
+    let credited = sub(amount, fee)?;
+
+    // Charged before the deposit joins, the depositor only shares in it for what they already had
+    pool.accrue(timestamp)?;
+    pool.charge_fee(fee)?;
+    position.settle(pool)?;
+
+    position.amount = add(position.amount, credited)?;
+    position.reset_debt(pool)?;
+    pool.total_deposited = add(pool.total_deposited, credited)?;
+
+    Ok(Deposit { amount: credited, fee })
+}
+
+pub fn withdraw(
+    pool: &mut Pool,
+    position: &mut Position,
+    amount: u64,
+    withdraw_fee: u64,
+    timestamp: u64
+) -> Result<Withdrawal> {
+    if amount > position.amount {
+        return Err(AccountingError::InsufficientStake);
+    }
+    pool.accrue(timestamp)?;
+    position.settle(pool)?;
+
+    position.amount = sub(position.amount, amount)?;
+    position.reset_debt(pool)?;
+    pool.total_deposited = sub(pool.total_deposited, amount)?;
+
+    // Charged after the withdrawal leaves, so it goes to whoever stays
+    let fee = calculate_fee(withdraw_fee, amount)?;
+    pool.charge_fee(fee)?;
+
+    Ok(Withdrawal { amount: sub(amount, fee)?, fee })
+}
+
+// Pays out stake that already left the pool, charging the withdraw fee on the way out
+pub fn release(pool: &mut Pool, amount: u64, withdraw_fee: u64) -> Result<Withdrawal> {
+    let fee = calculate_fee(withdraw_fee, amount)?;
+    pool.charge_fee(fee)?;
+    Ok(Withdrawal { amount: amount - fee, fee })
+}
+
+// Takes everything the position has earned up to the timestamp
+pub fn harvest(pool: &mut Pool, position: &mut Position, timestamp: u64) -> Result<u64> {
+    pool.accrue(timestamp)?;
+    position.settle(pool)?;
+    let rewards = position.unclaimed;
+    position.unclaimed = 0;
+    Ok(rewards)
+}
+
+// Takes the position's share of fees, paid in the deposit mint
+pub fn claim_fees(pool: &mut Pool, position: &mut Position) -> Result<u64> {
+    position.settle(pool)?;
+    let fees = position.unclaimed_fees;
+    position.unclaimed_fees = 0;
+    pool.staker_fees = sub(pool.staker_fees, fees)?;
+    Ok(fees)
+}
+
+// Settles the position and then sets its stake outright, as when it follows a receipt balance
+pub fn restake(
+    pool: &mut Pool,
+    position: &mut Position,
+    amount: u64,
+    timestamp: u64
+) -> Result<()> {
+    pool.accrue(timestamp)?;
+    position.settle(pool)?;
+    pool.total_deposited = add(sub(pool.total_deposited, position.amount)?, amount)?;
+    position.amount = amount;
+    position.reset_debt(pool)
+}
@@ -0,0 +1,196 @@
+// Random sequences of deposits, withdrawals, harvests and time passing across several farmers.
+// Each operation behaves like an instruction: if it fails, nothing it touched is kept.
+
+use proptest::prelude::*;
+use std::cmp;
+
+use farms_accounting::{self as accounting, Pool, Position, MAX_FEE};
+
+const FARMERS: usize = 4;
+
+#[derive(Clone, Debug)]
+enum Op {
+    Sow(usize, u64),
+    // The amount is taken modulo one more than the plot holds, so some of these overdraw
+    Uproot(usize, u64),
+    Harvest(usize),
+    Advance(u64),
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        (0..FARMERS, 1_000_000u64..1_000_000_000).prop_map(|(farmer, amount)| Op::Sow(farmer, amount)),
+        (0..FARMERS, any::<u64>()).prop_map(|(farmer, amount)| Op::Uproot(farmer, amount)),
+        (0..FARMERS).prop_map(Op::Harvest),
+        (0u64..86_400).prop_map(Op::Advance),
+    ]
+}
+
+#[derive(Clone, Debug)]
+struct Farm {
+    pool: Pool,
+    plots: [Position; FARMERS],
+    deposit_fee: u64,
+    withdraw_fee: u64,
+    start_timestamp: u64,
+    timestamp: u64,
+    deposit_treasury: u64,
+    reward_treasury: u64,
+    rewards_paid: u64,
+}
+
+impl Farm {
+
+    // The reward treasury is funded for the whole schedule up front
+    fn new(reward_rate: u64, duration: u64, deposit_fee: u64, withdraw_fee: u64, fee_share: u64) -> Self {
+        let start_timestamp = 1_600_000_000;
+        Farm {
+            pool: Pool {
+                reward_rate,
+                end_timestamp: start_timestamp + duration,
+                previous_reward_timestamp: start_timestamp,
+                fee_share,
+                ..Pool::default()
+            },
+            plots: [Position::default(); FARMERS],
+            deposit_fee,
+            withdraw_fee,
+            start_timestamp,
+            timestamp: start_timestamp,
+            deposit_treasury: 0,
+            reward_treasury: reward_rate * duration,
+            rewards_paid: 0,
+        }
+    }
+
+    fn apply(&mut self, op: &Op) -> accounting::Result<()> {
+        match *op {
+            Op::Sow(farmer, amount) => {
+                accounting::deposit(&mut self.pool, &mut self.plots[farmer], amount, self.deposit_fee, self.timestamp)?;
+                self.deposit_treasury += amount;
+            }
+            Op::Uproot(farmer, amount) => {
+                let amount = amount % (self.plots[farmer].amount + 2);
+                self.harvest(farmer)?;
+                let withdrawal = accounting::withdraw(&mut self.pool, &mut self.plots[farmer], amount, self.withdraw_fee, self.timestamp)?;
+                self.deposit_treasury -= withdrawal.amount;
+            }
+            Op::Harvest(farmer) => {
+                self.harvest(farmer)?;
+            }
+            Op::Advance(seconds) => {
+                self.timestamp += seconds;
+            }
+        }
+        Ok(())
+    }
+
+    fn harvest(&mut self, farmer: usize) -> accounting::Result<()> {
+        let rewards = accounting::harvest(&mut self.pool, &mut self.plots[farmer], self.timestamp)?;
+        self.reward_treasury = self.reward_treasury.checked_sub(rewards).expect("reward treasury overdrawn");
+        self.rewards_paid += rewards;
+        let fees = accounting::claim_fees(&mut self.pool, &mut self.plots[farmer])?;
+        self.deposit_treasury = self.deposit_treasury.checked_sub(fees).expect("deposit treasury overdrawn");
+        Ok(())
+    }
+
+    fn check(&self) {
+        let staked: u128 = self.plots.iter().map(|plot| plot.amount as u128).sum();
+        assert_eq!(self.pool.total_deposited as u128, staked);
+
+        // Fees stay in the treasury until they are collected or claimed by stakers
+        assert_eq!(
+            self.deposit_treasury as u128,
+            self.pool.total_deposited as u128 + self.pool.fees as u128 + self.pool.staker_fees as u128
+        );
+        let fees_owed: u128 = self.plots.iter()
+            .map(|plot| plot.unclaimed_fees as u128 + plot.pending_fees(&self.pool).unwrap() as u128)
+            .sum();
+        assert!(fees_owed <= self.pool.staker_fees as u128);
+
+        let emitted = self.pool.reward_rate as u128
+            * (cmp::min(self.timestamp, self.pool.end_timestamp) - self.start_timestamp) as u128;
+        let owed: u128 = self.plots.iter()
+            .map(|plot| plot.unclaimed as u128 + plot.pending(&self.pool).unwrap() as u128)
+            .sum();
+        assert!(self.rewards_paid as u128 + owed <= emitted);
+    }
+
+}
+
+proptest! {
+
+    #[test]
+    fn conserves_deposits_and_rewards(
+        reward_rate in 0u64..10_000,
+        duration in 1u64..1_000_000,
+        deposit_fee in 0..=MAX_FEE / 10,
+        withdraw_fee in 0..=MAX_FEE / 10,
+        fee_share in 0..=MAX_FEE,
+        ops in prop::collection::vec(op(), 1..64)
+    ) {
+        let mut farm = Farm::new(reward_rate, duration, deposit_fee, withdraw_fee, fee_share);
+        for op in ops.iter() {
+            let before = farm.clone();
+            if farm.apply(op).is_err() {
+                farm = before;
+            }
+            farm.check();
+        }
+    }
+
+    #[test]
+    fn harvesting_twice_pays_nothing_more(
+        reward_rate in 1u64..10_000,
+        amount in 1_000_000u64..1_000_000_000,
+        elapsed in 0u64..86_400
+    ) {
+        let mut farm = Farm::new(reward_rate, 1_000_000, 0, 0, 0);
+        farm.apply(&Op::Sow(0, amount)).unwrap();
+        farm.apply(&Op::Advance(elapsed)).unwrap();
+        farm.apply(&Op::Harvest(0)).unwrap();
+        let paid = farm.rewards_paid;
+        farm.apply(&Op::Harvest(0)).unwrap();
+        prop_assert_eq!(farm.rewards_paid, paid);
+    }
+
+    #[test]
+    fn penalty_never_grows_with_age(
+        penalty_max in 0..=MAX_FEE,
+        penalty_period in 0u64..10_000_000,
+        age in 0u64..20_000_000,
+        later in 0u64..1_000_000
+    ) {
+        let penalty = accounting::calculate_penalty_fee(penalty_max, penalty_period, age).unwrap();
+        prop_assert!(penalty <= penalty_max);
+        prop_assert!(accounting::calculate_penalty_fee(penalty_max, penalty_period, age + later).unwrap() <= penalty);
+    }
+
+    #[test]
+    fn extended_periods_never_emit_more_than_funded(
+        reward_rate in 0u64..1_000_000,
+        end_timestamp in 0u64..10_000_000,
+        timestamp in 0u64..10_000_000,
+        amount in 0u64..1_000_000_000_000,
+        duration in 1u64..10_000_000
+    ) {
+        let mut pool = accounting::Pool { reward_rate, end_timestamp, ..Default::default() };
+        let leftover = end_timestamp.saturating_sub(timestamp) as u128 * reward_rate as u128;
+        pool.extend_rewards(amount, duration, timestamp).unwrap();
+        prop_assert_eq!(pool.end_timestamp, timestamp + duration);
+        prop_assert!(pool.reward_rate as u128 * duration as u128 <= amount as u128 + leftover);
+    }
+
+    #[test]
+    fn apportioned_rates_never_exceed_emissions(
+        emission_rate in any::<u64>(),
+        alloc_points in prop::collection::vec(0u64..1_000_000, 1..16)
+    ) {
+        let total: u64 = alloc_points.iter().sum();
+        let apportioned: u128 = alloc_points.iter()
+            .map(|points| accounting::apportion_rate(emission_rate, *points, total).unwrap() as u128)
+            .sum();
+        prop_assert!(apportioned <= emission_rate as u128);
+    }
+
+}
@@ -0,0 +1,709 @@
+use anyhow::{anyhow, Context, Result};
+use clap::{Parser, Subcommand};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    instruction::Instruction,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::{read_keypair_file, Keypair, Signer},
+    sysvar::{self, clock::Clock},
+    transaction::Transaction,
+};
+
+use farms_client::{instructions, pda, state};
+use farms_client::state::{Crop, CropParams, Manager, Metadata, MetadataParams, Penalty, Plot};
+
+// Crops queued per apportion or rotate transaction, each of them is another account in it
+const APPORTION_CHUNK: usize = 16;
+
+#[derive(Parser)]
+#[clap(name = "farms-cli", about = "Manage farms from the command line")]
+struct Cli {
+
+    #[clap(long, default_value = "http://127.0.0.1:8899")]
+    url: String,
+
+    // Defaults to the Solana CLI keypair
+    #[clap(long)]
+    keypair: Option<String>,
+
+    #[clap(subcommand)]
+    command: Command,
+
+}
+
+#[derive(Subcommand)]
+enum Command {
+
+    /// Create a manager owned by the keypair
+    Appoint {
+        /// Seconds updates wait before they can be applied, at least a day
+        #[clap(long, default_value = "86400")]
+        timelock_delay: u64,
+        #[clap(long)]
+        max_fee: u64,
+    },
+
+    /// Transfer ownership of a manager
+    Entrust {
+        #[clap(long)]
+        manager: Pubkey,
+        #[clap(long)]
+        trustee: Pubkey,
+    },
+
+    /// Lower a manager's fee ceiling, it cannot be raised again
+    Hedge {
+        #[clap(long)]
+        manager: Pubkey,
+        #[clap(long)]
+        max_fee: u64,
+    },
+
+    /// Create a crop under a manager
+    Cultivate {
+        #[clap(long)]
+        manager: Pubkey,
+        #[clap(long)]
+        deposit_mint: Pubkey,
+        #[clap(long)]
+        reward_mint: Pubkey,
+        /// Share an existing reward treasury instead of creating one
+        #[clap(long)]
+        reward_treasury: Option<Pubkey>,
+        #[clap(flatten)]
+        params: Params,
+        #[clap(flatten)]
+        label: LabelArgs,
+    },
+
+    /// Set a crop's name, uri and tags
+    Label {
+        #[clap(long)]
+        manager: Pubkey,
+        #[clap(long)]
+        crop_id: u64,
+        #[clap(flatten)]
+        label: LabelArgs,
+    },
+
+    /// Queue an update to a crop
+    Recultivate {
+        #[clap(long)]
+        manager: Pubkey,
+        #[clap(long)]
+        crop_id: u64,
+        #[clap(flatten)]
+        params: Params,
+        /// Defaults to the earliest timestamp the manager's timelock allows
+        #[clap(long)]
+        effective_timestamp: Option<u64>,
+    },
+
+    /// Queue a split of an emission rate across every crop of a manager
+    Apportion {
+        #[clap(long)]
+        manager: Pubkey,
+        #[clap(long)]
+        emission_rate: u64,
+        /// Allocation points of every crop in id order, comma separated
+        #[clap(long, use_delimiter = true)]
+        alloc_points: Vec<u64>,
+        /// Defaults to the earliest timestamp the manager's timelock allows
+        #[clap(long)]
+        effective_timestamp: Option<u64>,
+    },
+
+    /// Switch every crop over to the queued apportionment once it is effective, anyone can do this
+    Rotate {
+        #[clap(long)]
+        manager: Pubkey,
+    },
+
+    /// Apply the queued updates of one or more crops once they are effective, anyone can do this
+    Ripen {
+        #[clap(long)]
+        manager: Pubkey,
+        /// Repeat to ripen several crops
+        #[clap(long, required = true)]
+        crop_id: Vec<u64>,
+    },
+
+    /// Collect fees from one or more crops in one transaction
+    Collect {
+        #[clap(long)]
+        manager: Pubkey,
+        /// Repeat to collect from several crops
+        #[clap(long, required = true)]
+        crop_id: Vec<u64>,
+        /// One per crop id, in the same order
+        #[clap(long, required = true)]
+        destination: Vec<Pubkey>,
+        /// Defaults to all of the fees, only valid for a single crop
+        #[clap(long)]
+        amount: Option<u64>,
+    },
+
+    /// Collect a crop's fees to the fee recipient once they are due, anyone can do this
+    Reap {
+        #[clap(long)]
+        manager: Pubkey,
+        #[clap(long)]
+        crop_id: u64,
+        #[clap(long)]
+        destination: Pubkey,
+    },
+
+    /// Accrue a crop's rewards up to now, anyone can do this
+    Tend {
+        #[clap(long)]
+        manager: Pubkey,
+        #[clap(long)]
+        crop_id: u64,
+    },
+
+    /// Set who fees are collected to and how often anyone can collect them
+    Consign {
+        #[clap(long)]
+        manager: Pubkey,
+        #[clap(long)]
+        fee_recipient: Pubkey,
+        /// Zero turns off collections by anyone
+        #[clap(long, default_value = "0")]
+        collect_interval: u64,
+    },
+
+    /// Transfer reward tokens into a crop's reward treasury and its budget
+    Fund {
+        #[clap(long)]
+        manager: Pubkey,
+        #[clap(long)]
+        crop_id: u64,
+        #[clap(long)]
+        source: Pubkey,
+        #[clap(long)]
+        amount: u64,
+    },
+
+    /// Fund a crop's reward period of the given length, rolling in what the current one has left
+    Irrigate {
+        #[clap(long)]
+        manager: Pubkey,
+        #[clap(long)]
+        crop_id: u64,
+        #[clap(long)]
+        source: Pubkey,
+        #[clap(long)]
+        amount: u64,
+        /// In seconds
+        #[clap(long)]
+        duration: u64,
+    },
+
+    /// Move reward budget between two crops sharing a reward treasury
+    Reallot {
+        #[clap(long)]
+        manager: Pubkey,
+        #[clap(long)]
+        from_crop_id: u64,
+        #[clap(long)]
+        to_crop_id: u64,
+        #[clap(long)]
+        amount: u64,
+    },
+
+    /// Let farmers move stake between two crops without paying fees
+    Chart {
+        #[clap(long)]
+        manager: Pubkey,
+        #[clap(long)]
+        from_crop_id: u64,
+        #[clap(long)]
+        to_crop_id: u64,
+    },
+
+    /// Remove a fee-free route between two crops
+    Unchart {
+        #[clap(long)]
+        manager: Pubkey,
+        #[clap(long)]
+        from_crop_id: u64,
+        #[clap(long)]
+        to_crop_id: u64,
+    },
+
+    /// Print a manager
+    ShowManager {
+        #[clap(long)]
+        manager: Pubkey,
+    },
+
+    /// Print every crop of a manager
+    ListCrops {
+        #[clap(long)]
+        manager: Pubkey,
+    },
+
+    /// Print a farmer's plot and pending rewards
+    ShowPlot {
+        #[clap(long)]
+        manager: Pubkey,
+        #[clap(long)]
+        crop_id: u64,
+        #[clap(long)]
+        farmer: Pubkey,
+        /// Also print the stake at this past timestamp, from the plot's and crop's checkpoints
+        #[clap(long)]
+        at: Option<u64>,
+    },
+
+}
+
+#[derive(clap::Args)]
+struct Params {
+
+    #[clap(long, default_value = "0")]
+    deposit_fee: u64,
+
+    #[clap(long, default_value = "0")]
+    withdraw_fee: u64,
+
+    #[clap(long, default_value = "0")]
+    referral_fee: u64,
+
+    #[clap(long)]
+    end_timestamp: u64,
+
+    #[clap(long)]
+    reward_rate: u64,
+
+    /// Withdraw fee right after a deposit, falling to zero over the penalty period. Replaces the
+    /// flat withdraw fee when set.
+    #[clap(long, default_value = "0")]
+    penalty_max: u64,
+
+    #[clap(long, default_value = "0")]
+    penalty_period: u64,
+
+    /// Part of every fee paid to stakers instead of the fee collector
+    #[clap(long, default_value = "0")]
+    fee_share: u64,
+
+    /// Seconds between requesting a withdrawal and being able to uproot it
+    #[clap(long, default_value = "0")]
+    cooldown: u64,
+
+}
+
+#[derive(clap::Args)]
+struct LabelArgs {
+
+    #[clap(long)]
+    name: Option<String>,
+
+    #[clap(long, default_value = "")]
+    uri: String,
+
+    /// Repeat for several tags
+    #[clap(long = "tag")]
+    tags: Vec<String>,
+
+}
+
+impl From<&Params> for Penalty {
+    fn from(params: &Params) -> Self {
+        Penalty {
+            max: params.penalty_max,
+            period: params.penalty_period,
+        }
+    }
+}
+
+impl From<&Params> for CropParams {
+    fn from(params: &Params) -> Self {
+        CropParams {
+            deposit_fee: params.deposit_fee,
+            withdraw_fee: params.withdraw_fee,
+            referral_fee: params.referral_fee,
+            end_timestamp: params.end_timestamp,
+            reward_rate: params.reward_rate,
+        }
+    }
+}
+
+struct Farms {
+    client: RpcClient,
+    payer: Keypair,
+}
+
+impl Farms {
+
+    fn send(&self, instructions: &[Instruction], signers: &[&Keypair]) -> Result<()> {
+        let mut all_signers = vec![&self.payer];
+        all_signers.extend_from_slice(signers);
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.payer.pubkey()),
+            &all_signers,
+            self.client.get_latest_blockhash()?
+        );
+        let signature = self.client.send_and_confirm_transaction(&transaction)?;
+        println!("signature: {}", signature);
+        Ok(())
+    }
+
+    fn timestamp(&self) -> Result<u64> {
+        let account = self.client.get_account(&sysvar::clock::id())?;
+        let clock: Clock = bincode::deserialize(&account.data)?;
+        Ok(clock.unix_timestamp as u64)
+    }
+
+    fn manager(&self, address: &Pubkey) -> Result<Manager> {
+        let data = self.client.get_account_data(address)?;
+        state::decode_manager(&data).map_err(|err| anyhow!("{} is not a manager: {}", address, err))
+    }
+
+    fn crop(&self, manager: &Pubkey, crop_id: u64) -> Result<Crop> {
+        let (address, _) = pda::find_crop(manager, crop_id);
+        let data = self.client.get_account_data(&address)?;
+        state::decode_crop(&data).map_err(|err| anyhow!("{} is not a crop: {}", address, err))
+    }
+
+    fn metadata(&self, crop: &Pubkey) -> Option<Metadata> {
+        let (address, _) = pda::find_metadata(crop);
+        let data = self.client.get_account_data(&address).ok()?;
+        state::decode_metadata(&data).ok()
+    }
+
+    fn plot(&self, manager: &Pubkey, crop_id: u64, farmer: &Pubkey) -> Result<Plot> {
+        let (address, _) = pda::find_plot(manager, farmer, crop_id);
+        let data = self.client.get_account_data(&address)?;
+        state::decode_plot(&data).map_err(|err| anyhow!("{} is not a plot: {}", address, err))
+    }
+
+}
+
+fn print_manager(address: &Pubkey, manager: &Manager) {
+    println!("manager:         {}", address);
+    println!("owner:           {}", manager.owner);
+    println!("operator:        {}", manager.operator);
+    println!("fee collector:   {}", manager.fee_collector);
+    println!("fee recipient:   {}", manager.fee_recipient());
+    println!("collect every:   {}", manager.collect_interval);
+    println!("pauser:          {}", manager.pauser);
+    println!("rewarder:        {}", pda::find_rewarder(address).0);
+    println!("crops:           {}", manager.crops);
+    println!("timelock delay:  {}", manager.timelock_delay);
+    println!("max fee:         {}", manager.max_fee);
+    println!("emission rate:   {}", manager.emission_rate);
+    println!("alloc points:    {}", manager.total_alloc_points);
+    if manager.pending_apportion_timestamp > 0 {
+        println!(
+            "queued split:    {} over {} points, {}/{} crops, effective at {}",
+            manager.pending_emission_rate,
+            manager.pending_total_alloc_points,
+            manager.apportioned_crops,
+            manager.crops,
+            manager.pending_apportion_timestamp
+        );
+        println!("rotated:         {}/{} crops", manager.rotated_crops, manager.crops);
+    }
+}
+
+fn print_crop(crop: &Crop, metadata: Option<Metadata>, timestamp: u64) -> Result<()> {
+    let projected = state::project_crop(crop, timestamp)?;
+    println!("crop:                  {}", pda::find_crop(&crop.manager, crop.id).0);
+    println!("id:                    {}", crop.id);
+    if let Some(metadata) = metadata {
+        println!("name:                  {}", metadata.name);
+        println!("uri:                   {}", metadata.uri);
+        println!("tags:                  {}", metadata.tags.join(", "));
+    }
+    println!("deposit mint:          {}", crop.deposit_mint);
+    println!("deposit treasury:      {}", crop.deposit_treasury);
+    println!("reward mint:           {}", crop.reward_mint);
+    println!("reward treasury:       {}", crop.reward_treasury);
+    println!("receipt mint:          {}", crop.receipt_mint);
+    println!("deposit fee:           {}", crop.deposit_fee);
+    println!("withdraw fee:          {}", crop.withdraw_fee);
+    println!("penalty:               {} over {}s", crop.penalty.max, crop.penalty.period);
+    println!("referral fee:          {}", crop.referral_fee);
+    println!("reward rate:           {}", crop.reward_rate);
+    println!("alloc points:          {}", crop.alloc_points);
+    if crop.pending_apportion {
+        println!("queued alloc points:   {}", crop.pending_alloc_points);
+    }
+    println!("reward budget:         {} ({} paid)", crop.reward_budget, crop.rewards_paid);
+    println!("end timestamp:         {}", crop.end_timestamp);
+    println!("total deposited:       {}", crop.total_deposited);
+    println!("fees:                  {}", crop.fees);
+    println!("fee share:             {} ({} held for stakers)", crop.fee_share, crop.staker_fees);
+    println!("cooldown:              {}s", crop.cooldown);
+    println!("rewards per share:     {} (projected {})", crop.rewards_per_share, projected.rewards_per_share);
+    println!("last reward timestamp: {}", crop.previous_reward_timestamp);
+    println!("paused:                {}", crop.paused);
+    if crop.pending_timestamp > 0 {
+        let pending = &crop.pending_params;
+        println!(
+            "pending update:        at {} deposit fee {} withdraw fee {} referral fee {} reward rate {} end timestamp {}",
+            crop.pending_timestamp,
+            pending.deposit_fee,
+            pending.withdraw_fee,
+            pending.referral_fee,
+            pending.reward_rate,
+            pending.end_timestamp
+        );
+        println!(
+            "pending penalty:       {} over {}s",
+            crop.pending_penalty.max,
+            crop.pending_penalty.period
+        );
+        println!("pending fee share:     {}", crop.pending_fee_share);
+        println!("pending cooldown:      {}s", crop.pending_cooldown);
+    }
+    Ok(())
+}
+
+fn default_keypair_path() -> Result<String> {
+    let home = std::env::var("HOME").context("HOME is not set, pass --keypair")?;
+    Ok(format!("{}/.config/solana/id.json", home))
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+
+    let keypair_path = match cli.keypair {
+        Some(path) => path,
+        None => default_keypair_path()?,
+    };
+    let payer = read_keypair_file(&keypair_path)
+        .map_err(|err| anyhow!("failed to read keypair {}: {}", keypair_path, err))?;
+    let farms = Farms {
+        client: RpcClient::new_with_commitment(cli.url, CommitmentConfig::confirmed()),
+        payer,
+    };
+    let signer = farms.payer.pubkey();
+
+    match cli.command {
+
+        Command::Appoint { timelock_delay, max_fee } => {
+            let manager = Keypair::new();
+            farms.send(
+                &[instructions::appoint(&manager.pubkey(), &signer, timelock_delay, max_fee)],
+                &[&manager]
+            )?;
+            println!("manager: {}", manager.pubkey());
+        }
+
+        Command::Entrust { manager, trustee } => {
+            farms.send(&[instructions::entrust(&manager, &signer, &trustee)], &[])?;
+        }
+
+        Command::Hedge { manager, max_fee } => {
+            farms.send(&[instructions::hedge(&manager, &signer, max_fee)], &[])?;
+        }
+
+        Command::Cultivate { manager, deposit_mint, reward_mint, reward_treasury, params, label } => {
+            let crop_id = farms.manager(&manager)?.crops;
+            let deposit_treasury = Keypair::new();
+            let new_reward_treasury = Keypair::new();
+            let (reward_treasury, signers) = match reward_treasury {
+                Some(reward_treasury) => (reward_treasury, vec![&deposit_treasury]),
+                None => (new_reward_treasury.pubkey(), vec![&deposit_treasury, &new_reward_treasury]),
+            };
+            let LabelArgs { name, uri, tags } = label;
+            let metadata = name.map(|name| MetadataParams { name, uri, tags });
+            farms.send(&[instructions::cultivate(
+                &manager,
+                crop_id,
+                &signer,
+                &deposit_mint,
+                &reward_mint,
+                &deposit_treasury.pubkey(),
+                &reward_treasury,
+                signers.len() > 1,
+                &CropParams::from(&params),
+                &Penalty::from(&params),
+                params.fee_share,
+                params.cooldown,
+                metadata
+            )], &signers)?;
+            println!("crop id: {}", crop_id);
+            println!("crop: {}", pda::find_crop(&manager, crop_id).0);
+        }
+
+        Command::Label { manager, crop_id, label } => {
+            let name = label.name.ok_or_else(|| anyhow!("--name is required"))?;
+            farms.send(&[instructions::label(&manager, crop_id, &signer, name, label.uri, label.tags)], &[])?;
+        }
+
+        Command::Recultivate { manager, crop_id, params, effective_timestamp } => {
+            let effective_timestamp = match effective_timestamp {
+                Some(timestamp) => timestamp,
+                None => farms.timestamp()? + farms.manager(&manager)?.timelock_delay,
+            };
+            farms.send(&[instructions::recultivate(
+                &manager,
+                crop_id,
+                &signer,
+                &CropParams::from(&params),
+                &Penalty::from(&params),
+                params.fee_share,
+                params.cooldown,
+                effective_timestamp
+            )], &[])?;
+            println!("effective at: {}", effective_timestamp);
+        }
+
+        Command::Apportion { manager, emission_rate, alloc_points, effective_timestamp } => {
+            let state = farms.manager(&manager)?;
+            if alloc_points.len() as u64 != state.crops {
+                return Err(anyhow!("expected allocation points for {} crops", state.crops));
+            }
+            let effective_timestamp = match effective_timestamp {
+                Some(timestamp) => timestamp,
+                None => farms.timestamp()? + state.timelock_delay,
+            };
+            // A transaction only fits so many crop accounts, larger managers take several
+            for (chunk, points) in alloc_points.chunks(APPORTION_CHUNK).enumerate() {
+                farms.send(&[instructions::apportion(
+                    &manager,
+                    &signer,
+                    emission_rate,
+                    (chunk * APPORTION_CHUNK) as u64,
+                    points.to_vec(),
+                    effective_timestamp
+                )], &[])?;
+            }
+            println!("effective at: {}", effective_timestamp);
+        }
+
+        Command::Rotate { manager } => {
+            // Picks up wherever an earlier, interrupted rotation stopped
+            let state = farms.manager(&manager)?;
+            let mut first_crop_id = state.rotated_crops;
+            while first_crop_id < state.crops {
+                let count = (state.crops - first_crop_id).min(APPORTION_CHUNK as u64);
+                farms.send(&[instructions::rotate(&manager, first_crop_id, count)], &[])?;
+                first_crop_id += count;
+            }
+        }
+
+        Command::Ripen { manager, crop_id } => {
+            let to_send: Vec<_> = crop_id.iter().map(|crop_id| instructions::ripen(&manager, *crop_id)).collect();
+            farms.send(&to_send, &[])?;
+        }
+
+        Command::Collect { manager, crop_id, destination, amount } => {
+            if crop_id.len() != destination.len() {
+                return Err(anyhow!("expected one destination per crop id"));
+            }
+            if amount.is_some() && crop_id.len() > 1 {
+                return Err(anyhow!("--amount only works with a single crop"));
+            }
+            let mut crops = Vec::new();
+            for (crop_id, destination) in crop_id.into_iter().zip(destination) {
+                let crop = farms.crop(&manager, crop_id)?;
+                println!("collecting from crop {}: {}", crop_id, amount.unwrap_or(crop.fees));
+                crops.push((crop, destination));
+            }
+            let to_send = match amount {
+                Some(_) => vec![instructions::collect(&crops[0].0, &signer, &crops[0].1, amount)],
+                None => instructions::collect_many(&crops, &signer),
+            };
+            farms.send(&to_send, &[])?;
+        }
+
+        Command::Reap { manager, crop_id, destination } => {
+            let crop = farms.crop(&manager, crop_id)?;
+            println!("collecting: {}", crop.fees);
+            farms.send(&[instructions::reap(&crop, &destination)], &[])?;
+        }
+
+        Command::Tend { manager, crop_id } => {
+            farms.send(&[instructions::tend(&farms.crop(&manager, crop_id)?)], &[])?;
+            let crop = farms.crop(&manager, crop_id)?;
+            println!("rewards per share: {}", crop.rewards_per_share);
+            println!("accrued up to:     {}", crop.previous_reward_timestamp);
+        }
+
+        Command::Consign { manager, fee_recipient, collect_interval } => {
+            farms.send(&[instructions::consign(&manager, &signer, &fee_recipient, collect_interval)], &[])?;
+        }
+
+        Command::Fund { manager, crop_id, source, amount } => {
+            let crop = farms.crop(&manager, crop_id)?;
+            farms.send(&[instructions::fertilize(&crop, &signer, &source, amount)], &[])?;
+            let data = farms.client.get_account_data(&crop.reward_treasury)?;
+            println!("reward treasury balance: {}", spl_token::state::Account::unpack(&data)?.amount);
+            let crop = farms.crop(&manager, crop_id)?;
+            println!("reward budget:           {}", crop.reward_budget - crop.rewards_paid);
+        }
+
+        Command::Irrigate { manager, crop_id, source, amount, duration } => {
+            let crop = farms.crop(&manager, crop_id)?;
+            farms.send(&[instructions::irrigate(&crop, &signer, &source, amount, duration)], &[])?;
+            let crop = farms.crop(&manager, crop_id)?;
+            println!("reward rate:   {}", crop.reward_rate);
+            println!("end timestamp: {}", crop.end_timestamp);
+        }
+
+        Command::Reallot { manager, from_crop_id, to_crop_id, amount } => {
+            farms.send(&[instructions::reallot(&manager, from_crop_id, to_crop_id, &signer, amount)], &[])?;
+        }
+
+        Command::Chart { manager, from_crop_id, to_crop_id } => {
+            farms.send(&[instructions::chart(&manager, from_crop_id, to_crop_id, &signer)], &[])?;
+            let from_crop = pda::find_crop(&manager, from_crop_id).0;
+            println!("route: {}", pda::find_route(&from_crop, &pda::find_crop(&manager, to_crop_id).0).0);
+        }
+
+        Command::Unchart { manager, from_crop_id, to_crop_id } => {
+            farms.send(&[instructions::unchart(&manager, from_crop_id, to_crop_id, &signer)], &[])?;
+        }
+
+        Command::ShowManager { manager } => {
+            print_manager(&manager, &farms.manager(&manager)?);
+        }
+
+        Command::ListCrops { manager } => {
+            let timestamp = farms.timestamp()?;
+            for crop_id in 0..farms.manager(&manager)?.crops {
+                let metadata = farms.metadata(&pda::find_crop(&manager, crop_id).0);
+                print_crop(&farms.crop(&manager, crop_id)?, metadata, timestamp)?;
+                println!();
+            }
+        }
+
+        Command::ShowPlot { manager, crop_id, farmer, at } => {
+            let crop = farms.crop(&manager, crop_id)?;
+            let plot = farms.plot(&manager, crop_id, &farmer)?;
+            let pending = state::pending_rewards(&crop, &plot, farms.timestamp()?)?;
+            println!("plot:            {}", pda::find_plot(&manager, &farmer, crop_id).0);
+            println!("amount:          {}", plot.amount);
+            println!("debt:            {}", plot.debt);
+            println!("unclaimed:       {}", plot.unclaimed);
+            println!("referrer:        {}", plot.referrer);
+            if plot.keeper != Pubkey::default() {
+                let scope = match plot.keeper_scope {
+                    state::KeeperScope::Harvest => "harvest",
+                    state::KeeperScope::Compound => "harvest and compound",
+                };
+                println!("keeper:          {} ({})", plot.keeper, scope);
+            }
+            println!("pending rewards: {}", pending);
+            println!("pending fees:    {}", state::pending_fees(&crop, &plot)?);
+            if plot.unbonding_amount > 0 {
+                println!("unbonding:       {} until {}", plot.unbonding_amount, plot.unbonding_timestamp);
+            }
+            if let Some(timestamp) = at {
+                let show = |amount: Option<u64>| amount.map_or("unknown".to_string(), |amount| amount.to_string());
+                println!("stake at {}: {} of {}", timestamp, show(state::stake_at(&plot, timestamp)), show(state::total_stake_at(&crop, timestamp)));
+            }
+        }
+
+    }
+
+    Ok(())
+}
@@ -0,0 +1,731 @@
+// Builders for every farms instruction. Instructions that only touch a crop's PDAs take the
+// manager and crop id, those that also need the treasuries or mints stored on the crop take the
+// decoded crop instead.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::{system_program, sysvar};
+use anchor_lang::{InstructionData, ToAccountMetas};
+use anchor_spl::associated_token::get_associated_token_address;
+
+use farms::state::{Crop, CropParams, KeeperScope, MetadataParams, Penalty, Plot, Role};
+
+use crate::pda;
+
+fn instruction(accounts: Vec<AccountMeta>, data: Vec<u8>) -> Instruction {
+    Instruction {
+        program_id: farms::id(),
+        accounts,
+        data,
+    }
+}
+
+// Accounts created from a fresh keypair have to sign for their own creation
+fn mark_signers(mut accounts: Vec<AccountMeta>, signers: &[Pubkey]) -> Vec<AccountMeta> {
+    for account in accounts.iter_mut() {
+        if signers.contains(&account.pubkey) {
+            account.is_signer = true;
+        }
+    }
+    accounts
+}
+
+fn writable(pubkey: Pubkey) -> AccountMeta {
+    AccountMeta::new(pubkey, false)
+}
+
+fn crop_address(crop: &Crop) -> Pubkey {
+    pda::find_crop(&crop.manager, crop.id).0
+}
+
+// The manager is a new keypair account and has to sign
+pub fn appoint(
+    manager: &Pubkey,
+    signer: &Pubkey,
+    timelock_delay: u64,
+    max_fee: u64
+) -> Instruction {
+    let (rewarder_pda, rewarder_bump) = pda::find_rewarder(manager);
+    let accounts = farms::accounts::Appoint {
+        manager: *manager,
+        rewarder_pda,
+        signer: *signer,
+        system_program: system_program::id(),
+        rent: sysvar::rent::id(),
+    };
+    instruction(
+        mark_signers(accounts.to_account_metas(None), &[*manager]),
+        farms::instruction::Appoint { rewarder_bump, timelock_delay, max_fee }.data()
+    )
+}
+
+pub fn entrust(manager: &Pubkey, owner: &Pubkey, trustee: &Pubkey) -> Instruction {
+    let accounts = farms::accounts::Entrust {
+        manager: *manager,
+        owner: *owner,
+    };
+    instruction(
+        accounts.to_account_metas(None),
+        farms::instruction::Entrust { trustee: *trustee }.data()
+    )
+}
+
+pub fn hedge(manager: &Pubkey, owner: &Pubkey, max_fee: u64) -> Instruction {
+    let accounts = farms::accounts::Hedge {
+        manager: *manager,
+        owner: *owner,
+    };
+    instruction(accounts.to_account_metas(None), farms::instruction::Hedge { max_fee }.data())
+}
+
+pub fn grant(manager: &Pubkey, owner: &Pubkey, role: Role, grantee: &Pubkey) -> Instruction {
+    let accounts = farms::accounts::Grant {
+        manager: *manager,
+        owner: *owner,
+    };
+    instruction(
+        accounts.to_account_metas(None),
+        farms::instruction::Grant { role, grantee: *grantee }.data()
+    )
+}
+
+pub fn revoke(manager: &Pubkey, owner: &Pubkey, role: Role) -> Instruction {
+    let accounts = farms::accounts::Revoke {
+        manager: *manager,
+        owner: *owner,
+    };
+    instruction(
+        accounts.to_account_metas(None),
+        farms::instruction::Revoke { role }.data()
+    )
+}
+
+// The deposit treasury is always a new keypair account. The reward treasury can be shared
+// between crops, and only has to sign when this crop creates it. Metadata given here is written
+// along with the crop.
+#[allow(clippy::too_many_arguments)]
+pub fn cultivate(
+    manager: &Pubkey,
+    crop_id: u64,
+    operator: &Pubkey,
+    deposit_mint: &Pubkey,
+    reward_mint: &Pubkey,
+    deposit_treasury: &Pubkey,
+    reward_treasury: &Pubkey,
+    new_reward_treasury: bool,
+    params: &CropParams,
+    penalty: &Penalty,
+    fee_share: u64,
+    cooldown: u64,
+    metadata: Option<MetadataParams>
+) -> Instruction {
+    let (crop, seed) = pda::find_crop(manager, crop_id);
+    let accounts = farms::accounts::Cultivate {
+        manager: *manager,
+        rewarder_pda: pda::find_rewarder(manager).0,
+        crop,
+        deposit_treasury: *deposit_treasury,
+        reward_treasury: *reward_treasury,
+        deposit_mint: *deposit_mint,
+        reward_mint: *reward_mint,
+        operator: *operator,
+        token_program: anchor_spl::token::ID,
+        system_program: system_program::id(),
+        rent: sysvar::rent::id(),
+    };
+    let mut signers = vec![*deposit_treasury];
+    if new_reward_treasury {
+        signers.push(*reward_treasury);
+    }
+    let mut metas = mark_signers(accounts.to_account_metas(None), &signers);
+    if metadata.is_some() {
+        metas.push(writable(pda::find_metadata(&crop).0));
+    }
+    instruction(
+        metas,
+        farms::instruction::Cultivate {
+            seed,
+            deposit_fee: params.deposit_fee,
+            withdraw_fee: params.withdraw_fee,
+            referral_fee: params.referral_fee,
+            end_timestamp: params.end_timestamp,
+            reward_rate: params.reward_rate,
+            penalty: *penalty,
+            fee_share,
+            cooldown,
+            metadata,
+        }.data()
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn recultivate(
+    manager: &Pubkey,
+    crop_id: u64,
+    operator: &Pubkey,
+    params: &CropParams,
+    penalty: &Penalty,
+    fee_share: u64,
+    cooldown: u64,
+    effective_timestamp: u64
+) -> Instruction {
+    let accounts = farms::accounts::Recultivate {
+        manager: *manager,
+        crop: pda::find_crop(manager, crop_id).0,
+        operator: *operator,
+    };
+    instruction(
+        accounts.to_account_metas(None),
+        farms::instruction::Recultivate {
+            deposit_fee: params.deposit_fee,
+            withdraw_fee: params.withdraw_fee,
+            referral_fee: params.referral_fee,
+            end_timestamp: params.end_timestamp,
+            reward_rate: params.reward_rate,
+            penalty: *penalty,
+            fee_share,
+            cooldown,
+            effective_timestamp,
+        }.data()
+    )
+}
+
+pub fn ripen(manager: &Pubkey, crop_id: u64) -> Instruction {
+    let accounts = farms::accounts::Ripen {
+        manager: *manager,
+        crop: pda::find_crop(manager, crop_id).0,
+    };
+    instruction(accounts.to_account_metas(None), farms::instruction::Ripen {}.data())
+}
+
+pub fn prune(manager: &Pubkey, crop_id: u64, operator: &Pubkey) -> Instruction {
+    let accounts = farms::accounts::Prune {
+        manager: *manager,
+        crop: pda::find_crop(manager, crop_id).0,
+        operator: *operator,
+    };
+    instruction(accounts.to_account_metas(None), farms::instruction::Prune {}.data())
+}
+
+pub fn fallow(manager: &Pubkey, crop_id: u64, pauser: &Pubkey, paused: bool) -> Instruction {
+    let accounts = farms::accounts::Fallow {
+        manager: *manager,
+        crop: pda::find_crop(manager, crop_id).0,
+        pauser: *pauser,
+    };
+    instruction(
+        accounts.to_account_metas(None),
+        farms::instruction::Fallow { paused }.data()
+    )
+}
+
+pub fn till(manager: &Pubkey, crop_id: u64, farmer: &Pubkey) -> Instruction {
+    let (plot, seed) = pda::find_plot(manager, farmer, crop_id);
+    let accounts = farms::accounts::Till {
+        manager: *manager,
+        plot,
+        farmer: *farmer,
+        system_program: system_program::id(),
+        rent: sysvar::rent::id(),
+    };
+    instruction(
+        accounts.to_account_metas(None),
+        farms::instruction::Till { seed, crop_id }.data()
+    )
+}
+
+// The farmer's associated receipt token account is required when the crop issues receipts. A referral account is
+// only read when the plot has no referrer yet, but passing it again is harmless.
+pub fn sow(
+    crop: &Crop,
+    farmer: &Pubkey,
+    from_token_account: &Pubkey,
+    amount: u64,
+    referrer: Option<Pubkey>,
+    receipt_token_account: Option<Pubkey>
+) -> Instruction {
+    let crop_key = crop_address(crop);
+    let accounts = farms::accounts::Sow {
+        crop: crop_key,
+        plot: pda::find_plot(&crop.manager, farmer, crop.id).0,
+        deposit_treasury: crop.deposit_treasury,
+        from_token_account: *from_token_account,
+        farmer: *farmer,
+        token_program: anchor_spl::token::ID,
+    };
+    let mut metas = accounts.to_account_metas(None);
+    if crop.receipt_mint != Pubkey::default() {
+        metas.push(writable(crop.receipt_mint));
+        metas.push(writable(receipt_token_account.expect("crop issues receipts")));
+    }
+    if let Some(referrer) = referrer {
+        metas.push(writable(pda::find_referral(&crop_key, &referrer).0));
+    }
+    instruction(metas, farms::instruction::Sow { amount, referrer }.data())
+}
+
+// The farmer's associated receipt token account is required when withdrawing from a crop that
+// issues receipts
+#[allow(clippy::too_many_arguments)]
+pub fn uproot(
+    crop: &Crop,
+    plot: &Plot,
+    farmer: &Pubkey,
+    deposit_token_account: &Pubkey,
+    reward_token_account: &Pubkey,
+    amount: u64,
+    receipt_token_account: Option<Pubkey>
+) -> Instruction {
+    let crop_key = crop_address(crop);
+    let accounts = farms::accounts::Uproot {
+        manager: crop.manager,
+        rewarder_pda: pda::find_rewarder(&crop.manager).0,
+        crop: crop_key,
+        plot: pda::find_plot(&crop.manager, farmer, crop.id).0,
+        deposit_treasury: crop.deposit_treasury,
+        reward_treasury: crop.reward_treasury,
+        deposit_token_account: *deposit_token_account,
+        reward_token_account: *reward_token_account,
+        farmer: *farmer,
+        token_program: anchor_spl::token::ID,
+    };
+    let mut metas = accounts.to_account_metas(None);
+    if amount > 0 && crop.receipt_mint != Pubkey::default() {
+        metas.push(writable(crop.receipt_mint));
+        metas.push(writable(receipt_token_account.expect("crop issues receipts")));
+    }
+    if plot.referrer != Pubkey::default() {
+        metas.push(writable(pda::find_referral(&crop_key, &plot.referrer).0));
+    }
+    instruction(metas, farms::instruction::Uproot { amount }.data())
+}
+
+// Every crop must belong to the manager. Each comes with the farmer's plot in it and the farmer's
+// token accounts for its reward mint and its deposit mint, the latter receiving the farmer's share
+// of fees.
+pub fn harvest_many(manager: &Pubkey, farmer: &Pubkey, harvests: &[(Crop, Plot, Pubkey, Pubkey)]) -> Instruction {
+    let accounts = farms::accounts::HarvestMany {
+        manager: *manager,
+        rewarder_pda: pda::find_rewarder(manager).0,
+        farmer: *farmer,
+        token_program: anchor_spl::token::ID,
+    };
+    let mut metas = accounts.to_account_metas(None);
+    for (crop, plot, reward_token_account, deposit_token_account) in harvests {
+        let crop_key = crop_address(crop);
+        metas.push(writable(crop_key));
+        metas.push(writable(pda::find_plot(manager, farmer, crop.id).0));
+        metas.push(writable(crop.reward_treasury));
+        metas.push(writable(*reward_token_account));
+        metas.push(writable(crop.deposit_treasury));
+        metas.push(writable(*deposit_token_account));
+        if plot.referrer != Pubkey::default() {
+            metas.push(writable(pda::find_referral(&crop_key, &plot.referrer).0));
+        }
+    }
+    instruction(metas, farms::instruction::HarvestMany {}.data())
+}
+
+pub fn hire(crop: &Crop, farmer: &Pubkey, keeper: &Pubkey, scope: KeeperScope) -> Instruction {
+    let accounts = farms::accounts::Hire {
+        crop: crop_address(crop),
+        plot: pda::find_plot(&crop.manager, farmer, crop.id).0,
+        farmer: *farmer,
+    };
+    instruction(
+        accounts.to_account_metas(None),
+        farms::instruction::Hire { keeper: *keeper, scope }.data()
+    )
+}
+
+pub fn dismiss(crop: &Crop, farmer: &Pubkey) -> Instruction {
+    let accounts = farms::accounts::Dismiss {
+        crop: crop_address(crop),
+        plot: pda::find_plot(&crop.manager, farmer, crop.id).0,
+        farmer: *farmer,
+    };
+    instruction(accounts.to_account_metas(None), farms::instruction::Dismiss {}.data())
+}
+
+// The reward and deposit token accounts have to be owned by the farmer, whoever signs
+pub fn glean(
+    crop: &Crop,
+    plot: &Plot,
+    farmer: &Pubkey,
+    keeper: &Pubkey,
+    reward_token_account: &Pubkey,
+    deposit_token_account: &Pubkey
+) -> Instruction {
+    let crop_key = crop_address(crop);
+    let accounts = farms::accounts::Glean {
+        manager: crop.manager,
+        rewarder_pda: pda::find_rewarder(&crop.manager).0,
+        crop: crop_key,
+        plot: pda::find_plot(&crop.manager, farmer, crop.id).0,
+        reward_treasury: crop.reward_treasury,
+        reward_token_account: *reward_token_account,
+        deposit_treasury: crop.deposit_treasury,
+        deposit_token_account: *deposit_token_account,
+        farmer: *farmer,
+        keeper: *keeper,
+        token_program: anchor_spl::token::ID,
+    };
+    let mut metas = accounts.to_account_metas(None);
+    if plot.referrer != Pubkey::default() {
+        metas.push(writable(pda::find_referral(&crop_key, &plot.referrer).0));
+    }
+    instruction(metas, farms::instruction::Glean {}.data())
+}
+
+pub fn compound(crop: &Crop, plot: &Plot, farmer: &Pubkey, keeper: &Pubkey) -> Instruction {
+    let crop_key = crop_address(crop);
+    let accounts = farms::accounts::Compound {
+        manager: crop.manager,
+        rewarder_pda: pda::find_rewarder(&crop.manager).0,
+        crop: crop_key,
+        plot: pda::find_plot(&crop.manager, farmer, crop.id).0,
+        reward_treasury: crop.reward_treasury,
+        deposit_treasury: crop.deposit_treasury,
+        farmer: *farmer,
+        keeper: *keeper,
+        token_program: anchor_spl::token::ID,
+    };
+    let mut metas = accounts.to_account_metas(None);
+    if plot.referrer != Pubkey::default() {
+        metas.push(writable(pda::find_referral(&crop_key, &plot.referrer).0));
+    }
+    instruction(metas, farms::instruction::Compound {}.data())
+}
+
+// Passes the pair's route whether or not the owner has charted it, fees are only waived if so
+pub fn migrate_stake(from_crop: &Crop, to_crop: &Crop, farmer: &Pubkey, amount: u64) -> Instruction {
+    let from_crop_key = crop_address(from_crop);
+    let to_crop_key = crop_address(to_crop);
+    let accounts = farms::accounts::MigrateStake {
+        manager: from_crop.manager,
+        from_crop: from_crop_key,
+        from_plot: pda::find_plot(&from_crop.manager, farmer, from_crop.id).0,
+        to_crop: to_crop_key,
+        to_plot: pda::find_plot(&to_crop.manager, farmer, to_crop.id).0,
+        from_deposit_treasury: from_crop.deposit_treasury,
+        to_deposit_treasury: to_crop.deposit_treasury,
+        route: pda::find_route(&from_crop_key, &to_crop_key).0,
+        farmer: *farmer,
+        token_program: anchor_spl::token::ID,
+    };
+    instruction(accounts.to_account_metas(None), farms::instruction::MigrateStake { amount }.data())
+}
+
+pub fn chart(manager: &Pubkey, from_crop_id: u64, to_crop_id: u64, owner: &Pubkey) -> Instruction {
+    let from_crop = pda::find_crop(manager, from_crop_id).0;
+    let to_crop = pda::find_crop(manager, to_crop_id).0;
+    let (route, seed) = pda::find_route(&from_crop, &to_crop);
+    let accounts = farms::accounts::Chart {
+        manager: *manager,
+        from_crop,
+        to_crop,
+        route,
+        owner: *owner,
+        system_program: system_program::id(),
+        rent: sysvar::rent::id(),
+    };
+    instruction(accounts.to_account_metas(None), farms::instruction::Chart { seed }.data())
+}
+
+pub fn unchart(manager: &Pubkey, from_crop_id: u64, to_crop_id: u64, owner: &Pubkey) -> Instruction {
+    let from_crop = pda::find_crop(manager, from_crop_id).0;
+    let to_crop = pda::find_crop(manager, to_crop_id).0;
+    let accounts = farms::accounts::Unchart {
+        manager: *manager,
+        from_crop,
+        route: pda::find_route(&from_crop, &to_crop).0,
+        owner: *owner,
+    };
+    instruction(accounts.to_account_metas(None), farms::instruction::Unchart {}.data())
+}
+
+pub fn request_uproot(crop: &Crop, farmer: &Pubkey, amount: u64) -> Instruction {
+    let accounts = farms::accounts::RequestUproot {
+        crop: crop_address(crop),
+        plot: pda::find_plot(&crop.manager, farmer, crop.id).0,
+        farmer: *farmer,
+    };
+    instruction(accounts.to_account_metas(None), farms::instruction::RequestUproot { amount }.data())
+}
+
+pub fn cancel_uproot(crop: &Crop, farmer: &Pubkey) -> Instruction {
+    let accounts = farms::accounts::CancelUproot {
+        crop: crop_address(crop),
+        plot: pda::find_plot(&crop.manager, farmer, crop.id).0,
+        farmer: *farmer,
+    };
+    instruction(accounts.to_account_metas(None), farms::instruction::CancelUproot {}.data())
+}
+
+pub fn collect(
+    crop: &Crop,
+    collector: &Pubkey,
+    deposit_token_account: &Pubkey,
+    amount: Option<u64>
+) -> Instruction {
+    let accounts = farms::accounts::Collect {
+        manager: crop.manager,
+        crop: crop_address(crop),
+        deposit_treasury: crop.deposit_treasury,
+        deposit_token_account: *deposit_token_account,
+        collector: *collector,
+        token_program: anchor_spl::token::ID,
+    };
+    instruction(accounts.to_account_metas(None), farms::instruction::Collect { amount }.data())
+}
+
+// Sweeps every crop in one transaction, each paired with the fee recipient's deposit token account.
+// There is no multi-crop collect in the program, send these together to collect atomically.
+pub fn collect_many(crops: &[(Crop, Pubkey)], collector: &Pubkey) -> Vec<Instruction> {
+    crops
+        .iter()
+        .map(|(crop, deposit_token_account)| collect(crop, collector, deposit_token_account, None))
+        .collect()
+}
+
+pub fn reap(crop: &Crop, deposit_token_account: &Pubkey) -> Instruction {
+    let accounts = farms::accounts::Reap {
+        manager: crop.manager,
+        crop: crop_address(crop),
+        deposit_treasury: crop.deposit_treasury,
+        deposit_token_account: *deposit_token_account,
+        token_program: anchor_spl::token::ID,
+    };
+    instruction(accounts.to_account_metas(None), farms::instruction::Reap {}.data())
+}
+
+pub fn consign(manager: &Pubkey, owner: &Pubkey, fee_recipient: &Pubkey, collect_interval: u64) -> Instruction {
+    let accounts = farms::accounts::Consign {
+        manager: *manager,
+        owner: *owner,
+    };
+    instruction(
+        accounts.to_account_metas(None),
+        farms::instruction::Consign { fee_recipient: *fee_recipient, collect_interval }.data()
+    )
+}
+
+pub fn appraise(manager: &Pubkey, crop_id: u64, farmer: &Pubkey) -> Instruction {
+    let accounts = farms::accounts::Appraise {
+        crop: pda::find_crop(manager, crop_id).0,
+        plot: pda::find_plot(manager, farmer, crop_id).0,
+        farmer: *farmer,
+    };
+    instruction(accounts.to_account_metas(None), farms::instruction::Appraise {}.data())
+}
+
+pub fn weigh(manager: &Pubkey, crop_id: u64, farmer: &Pubkey, timestamp: u64) -> Instruction {
+    let accounts = farms::accounts::Weigh {
+        crop: pda::find_crop(manager, crop_id).0,
+        plot: pda::find_plot(manager, farmer, crop_id).0,
+        farmer: *farmer,
+    };
+    instruction(accounts.to_account_metas(None), farms::instruction::Weigh { timestamp }.data())
+}
+
+pub fn tend(crop: &Crop) -> Instruction {
+    let accounts = farms::accounts::Tend {
+        crop: crop_address(crop),
+    };
+    instruction(accounts.to_account_metas(None), farms::instruction::Tend {}.data())
+}
+
+pub fn certify(crop: &Crop, operator: &Pubkey) -> Instruction {
+    let crop_key = crop_address(crop);
+    let (receipt_mint, seed) = pda::find_receipt_mint(&crop_key);
+    let accounts = farms::accounts::Certify {
+        manager: crop.manager,
+        crop: crop_key,
+        receipt_mint,
+        deposit_mint: crop.deposit_mint,
+        operator: *operator,
+        token_program: anchor_spl::token::ID,
+        system_program: system_program::id(),
+        rent: sysvar::rent::id(),
+    };
+    instruction(
+        accounts.to_account_metas(None),
+        farms::instruction::Certify { seed }.data()
+    )
+}
+
+// Settles the farmer's plot against their associated receipt token account. Anyone can call it to
+// bring the plot down, only the farmer to bring it up.
+pub fn reckon(crop: &Crop, farmer: &Pubkey, caller: &Pubkey) -> Instruction {
+    let accounts = farms::accounts::Reckon {
+        crop: crop_address(crop),
+        plot: pda::find_plot(&crop.manager, farmer, crop.id).0,
+        receipt_mint: crop.receipt_mint,
+        receipt_token_account: get_associated_token_address(farmer, &crop.receipt_mint),
+        farmer: *farmer,
+        caller: *caller,
+    };
+    instruction(accounts.to_account_metas(None), farms::instruction::Reckon {}.data())
+}
+
+pub fn enlist(manager: &Pubkey, crop_id: u64, referrer: &Pubkey) -> Instruction {
+    let crop = pda::find_crop(manager, crop_id).0;
+    let (referral, seed) = pda::find_referral(&crop, referrer);
+    let accounts = farms::accounts::Enlist {
+        crop,
+        referral,
+        referrer: *referrer,
+        system_program: system_program::id(),
+        rent: sysvar::rent::id(),
+    };
+    instruction(
+        accounts.to_account_metas(None),
+        farms::instruction::Enlist { seed }.data()
+    )
+}
+
+pub fn redeem(crop: &Crop, referrer: &Pubkey, reward_token_account: &Pubkey) -> Instruction {
+    let crop_key = crop_address(crop);
+    let accounts = farms::accounts::Redeem {
+        manager: crop.manager,
+        rewarder_pda: pda::find_rewarder(&crop.manager).0,
+        crop: crop_key,
+        referral: pda::find_referral(&crop_key, referrer).0,
+        reward_treasury: crop.reward_treasury,
+        reward_token_account: *reward_token_account,
+        referrer: *referrer,
+        token_program: anchor_spl::token::ID,
+    };
+    instruction(accounts.to_account_metas(None), farms::instruction::Redeem {}.data())
+}
+
+pub fn label(
+    manager: &Pubkey,
+    crop_id: u64,
+    operator: &Pubkey,
+    name: String,
+    uri: String,
+    tags: Vec<String>
+) -> Instruction {
+    let crop = pda::find_crop(manager, crop_id).0;
+    let (metadata, seed) = pda::find_metadata(&crop);
+    let accounts = farms::accounts::Label {
+        manager: *manager,
+        crop,
+        metadata,
+        operator: *operator,
+        system_program: system_program::id(),
+        rent: sysvar::rent::id(),
+    };
+    instruction(
+        accounts.to_account_metas(None),
+        farms::instruction::Label { seed, name, uri, tags }.data()
+    )
+}
+
+// Covers the crops from the first crop id on, the points are given in crop id order. Starting
+// from crop 0 begins a new apportionment, later calls continue it with the same rate and timestamp.
+pub fn apportion(
+    manager: &Pubkey,
+    operator: &Pubkey,
+    emission_rate: u64,
+    first_crop_id: u64,
+    alloc_points: Vec<u64>,
+    effective_timestamp: u64
+) -> Instruction {
+    let accounts = farms::accounts::Apportion {
+        manager: *manager,
+        operator: *operator,
+    };
+    let mut metas = accounts.to_account_metas(None);
+    for crop_id in first_crop_id..first_crop_id + alloc_points.len() as u64 {
+        metas.push(writable(pda::find_crop(manager, crop_id).0));
+    }
+    instruction(
+        metas,
+        farms::instruction::Apportion { emission_rate, alloc_points, effective_timestamp }.data()
+    )
+}
+
+// Covers count crops from the first crop id on, starting where the last rotate call stopped
+pub fn rotate(manager: &Pubkey, first_crop_id: u64, count: u64) -> Instruction {
+    let accounts = farms::accounts::Rotate {
+        manager: *manager,
+    };
+    let mut metas = accounts.to_account_metas(None);
+    for crop_id in first_crop_id..first_crop_id + count {
+        metas.push(writable(pda::find_crop(manager, crop_id).0));
+    }
+    instruction(metas, farms::instruction::Rotate {}.data())
+}
+
+pub fn fertilize(crop: &Crop, funder: &Pubkey, from_token_account: &Pubkey, amount: u64) -> Instruction {
+    let accounts = farms::accounts::Fertilize {
+        crop: crop_address(crop),
+        reward_treasury: crop.reward_treasury,
+        from_token_account: *from_token_account,
+        funder: *funder,
+        token_program: anchor_spl::token::ID,
+    };
+    instruction(accounts.to_account_metas(None), farms::instruction::Fertilize { amount }.data())
+}
+
+pub fn irrigate(
+    crop: &Crop,
+    operator: &Pubkey,
+    from_token_account: &Pubkey,
+    amount: u64,
+    duration: u64
+) -> Instruction {
+    let accounts = farms::accounts::Irrigate {
+        manager: crop.manager,
+        crop: crop_address(crop),
+        reward_treasury: crop.reward_treasury,
+        from_token_account: *from_token_account,
+        operator: *operator,
+        token_program: anchor_spl::token::ID,
+    };
+    instruction(accounts.to_account_metas(None), farms::instruction::Irrigate { amount, duration }.data())
+}
+
+pub fn reallot(manager: &Pubkey, from_crop_id: u64, to_crop_id: u64, operator: &Pubkey, amount: u64) -> Instruction {
+    let accounts = farms::accounts::Reallot {
+        manager: *manager,
+        from_crop: pda::find_crop(manager, from_crop_id).0,
+        to_crop: pda::find_crop(manager, to_crop_id).0,
+        operator: *operator,
+    };
+    instruction(accounts.to_account_metas(None), farms::instruction::Reallot { amount }.data())
+}
+
+pub fn migrate_manager(manager: &Pubkey, payer: &Pubkey) -> Instruction {
+    let accounts = farms::accounts::MigrateManager {
+        manager: *manager,
+        payer: *payer,
+        system_program: system_program::id(),
+        rent: sysvar::rent::id(),
+    };
+    instruction(accounts.to_account_metas(None), farms::instruction::MigrateManager {}.data())
+}
+
+// Takes the reward treasury separately, as crops on an older layout cannot be decoded yet
+pub fn migrate_crop(manager: &Pubkey, crop_id: u64, reward_treasury: &Pubkey, payer: &Pubkey) -> Instruction {
+    let accounts = farms::accounts::MigrateCrop {
+        crop: pda::find_crop(manager, crop_id).0,
+        reward_treasury: *reward_treasury,
+        payer: *payer,
+        system_program: system_program::id(),
+        rent: sysvar::rent::id(),
+    };
+    instruction(accounts.to_account_metas(None), farms::instruction::MigrateCrop {}.data())
+}
+
+pub fn migrate_plot(manager: &Pubkey, crop_id: u64, farmer: &Pubkey, payer: &Pubkey) -> Instruction {
+    let accounts = farms::accounts::MigratePlot {
+        plot: pda::find_plot(manager, farmer, crop_id).0,
+        payer: *payer,
+        system_program: system_program::id(),
+        rent: sysvar::rent::id(),
+    };
+    instruction(accounts.to_account_metas(None), farms::instruction::MigratePlot {}.data())
+}
@@ -0,0 +1,5 @@
+pub mod pda;
+pub mod instructions;
+pub mod state;
+
+pub use farms::ID as FARMS_PROGRAM_ID;
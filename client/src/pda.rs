@@ -0,0 +1,52 @@
+use anchor_lang::prelude::Pubkey;
+
+// These must match the seeds used by the program's account constraints
+
+pub fn find_rewarder(manager: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"rewarder".as_ref(), manager.as_ref()],
+        &farms::id()
+    )
+}
+
+pub fn find_crop(manager: &Pubkey, crop_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"crop".as_ref(), manager.as_ref(), &crop_id.to_le_bytes()],
+        &farms::id()
+    )
+}
+
+pub fn find_plot(manager: &Pubkey, farmer: &Pubkey, crop_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"plot".as_ref(), manager.as_ref(), farmer.as_ref(), &crop_id.to_le_bytes()],
+        &farms::id()
+    )
+}
+
+pub fn find_receipt_mint(crop: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"receipt".as_ref(), crop.as_ref()],
+        &farms::id()
+    )
+}
+
+pub fn find_referral(crop: &Pubkey, referrer: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"referral".as_ref(), crop.as_ref(), referrer.as_ref()],
+        &farms::id()
+    )
+}
+
+pub fn find_metadata(crop: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"metadata".as_ref(), crop.as_ref()],
+        &farms::id()
+    )
+}
+
+pub fn find_route(from_crop: &Pubkey, to_crop: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"route".as_ref(), from_crop.as_ref(), to_crop.as_ref()],
+        &farms::id()
+    )
+}
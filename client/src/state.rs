@@ -0,0 +1,67 @@
+use anchor_lang::prelude::ProgramError;
+use anchor_lang::AccountDeserialize;
+use farms::instructions::utils;
+
+pub use farms::state::{Manager, Crop, CropParams, Penalty, Plot, KeeperScope, Referral, Metadata, MetadataParams, Route, Role};
+
+// Each decoder checks the account discriminator, so passing the wrong kind of account fails.
+// Accounts on an older layout fail too until they are migrated.
+
+pub fn decode_manager(data: &[u8]) -> Result<Manager, ProgramError> {
+    Manager::try_deserialize(&mut &data[..])
+}
+
+pub fn decode_crop(data: &[u8]) -> Result<Crop, ProgramError> {
+    Crop::try_deserialize(&mut &data[..])
+}
+
+pub fn decode_plot(data: &[u8]) -> Result<Plot, ProgramError> {
+    Plot::try_deserialize(&mut &data[..])
+}
+
+pub fn decode_referral(data: &[u8]) -> Result<Referral, ProgramError> {
+    Referral::try_deserialize(&mut &data[..])
+}
+
+pub fn decode_metadata(data: &[u8]) -> Result<Metadata, ProgramError> {
+    Metadata::try_deserialize(&mut &data[..])
+}
+
+pub fn decode_route(data: &[u8]) -> Result<Route, ProgramError> {
+    Route::try_deserialize(&mut &data[..])
+}
+
+// The crop as it would be after accruing rewards up to the timestamp
+pub fn project_crop(crop: &Crop, timestamp: u64) -> Result<Crop, ProgramError> {
+    let mut crop = crop.clone();
+    utils::update_crop_at(&mut crop, timestamp)?;
+    Ok(crop)
+}
+
+// What the farmer would receive if they harvested at the timestamp, after any referral kickback
+// and within the crop's reward budget
+pub fn pending_rewards(crop: &Crop, plot: &Plot, timestamp: u64) -> Result<u64, ProgramError> {
+    let crop = project_crop(crop, timestamp)?;
+    let mut plot = plot.clone();
+    utils::settle_plot(&crop, &mut plot)?;
+    let payable = utils::payable_rewards(&crop, plot.unclaimed);
+    let (rewards, _) = utils::split_referral(&crop, &plot, payable)?;
+    Ok(rewards)
+}
+
+// Shared fees the farmer would receive in the deposit mint
+pub fn pending_fees(crop: &Crop, plot: &Plot) -> Result<u64, ProgramError> {
+    let mut plot = plot.clone();
+    utils::settle_plot(crop, &mut plot)?;
+    Ok(plot.unclaimed_fees)
+}
+
+// What the farmer had staked at the timestamp, None when it predates the plot's checkpoints
+pub fn stake_at(plot: &Plot, timestamp: u64) -> Option<u64> {
+    utils::amount_at(&plot.checkpoints, timestamp)
+}
+
+// What the crop had deposited in total at the timestamp, None when it predates the crop's checkpoints
+pub fn total_stake_at(crop: &Crop, timestamp: u64) -> Option<u64> {
+    utils::amount_at(&crop.checkpoints, timestamp)
+}
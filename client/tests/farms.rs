@@ -0,0 +1,1481 @@
+// Runs against the compiled program only. The native processor in solana-program-test 1.9 cannot
+// grow accounts created through a CPI, which every `init` does, so a plain `cargo test` skips
+// these. Build the program and point the tests at it:
+//
+//     cargo build-bpf --manifest-path programs/farms/Cargo.toml
+//     BPF_OUT_DIR=$PWD/target/deploy cargo test -p farms-client --features test-bpf
+#![cfg(feature = "test-bpf")]
+
+use anchor_lang::prelude::{AnchorSerialize, Pubkey};
+use anchor_spl::associated_token::get_associated_token_address;
+use solana_program_test::{ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::{Account, AccountSharedData},
+    instruction::Instruction,
+    program_pack::Pack,
+    signature::{Keypair, Signer},
+    system_instruction,
+    sysvar::clock::Clock,
+    transaction::Transaction,
+};
+
+use farms_client::{instructions, pda, state};
+use farms_client::state::{CropParams, MetadataParams, Penalty, Role};
+use farms::instructions::utils::{MAX_FEE, MIN_TIMELOCK_DELAY};
+use farms::state::{CropV0, ManagerV0, PlotV0};
+
+async fn start() -> ProgramTestContext {
+    ProgramTest::new("farms", farms::id(), None)
+        .start_with_context()
+        .await
+}
+
+async fn process(
+    context: &mut ProgramTestContext,
+    instructions: &[Instruction],
+    signers: &[&Keypair]
+) {
+    assert!(try_process(context, instructions, signers).await);
+}
+
+async fn try_process(
+    context: &mut ProgramTestContext,
+    instructions: &[Instruction],
+    signers: &[&Keypair]
+) -> bool {
+    let transaction = {
+        let mut all_signers = vec![&context.payer];
+        all_signers.extend_from_slice(signers);
+        Transaction::new_signed_with_payer(
+            instructions,
+            Some(&context.payer.pubkey()),
+            &all_signers,
+            context.last_blockhash
+        )
+    };
+    context.banks_client.process_transaction(transaction).await.is_ok()
+}
+
+async fn account_data(context: &mut ProgramTestContext, address: &Pubkey) -> Vec<u8> {
+    context.banks_client.get_account(*address).await.unwrap().unwrap().data
+}
+
+async fn create_mint(context: &mut ProgramTestContext) -> Pubkey {
+    let mint = Keypair::new();
+    let rent = context.banks_client.get_rent().await.unwrap();
+    let payer = context.payer.pubkey();
+    process(context, &[
+        system_instruction::create_account(
+            &payer,
+            &mint.pubkey(),
+            rent.minimum_balance(spl_token::state::Mint::LEN),
+            spl_token::state::Mint::LEN as u64,
+            &spl_token::id()
+        ),
+        spl_token::instruction::initialize_mint(&spl_token::id(), &mint.pubkey(), &payer, None, 9).unwrap(),
+    ], &[&mint]).await;
+    mint.pubkey()
+}
+
+async fn create_token_account(context: &mut ProgramTestContext, mint: &Pubkey, owner: &Pubkey) -> Pubkey {
+    let account = Keypair::new();
+    let rent = context.banks_client.get_rent().await.unwrap();
+    let payer = context.payer.pubkey();
+    process(context, &[
+        system_instruction::create_account(
+            &payer,
+            &account.pubkey(),
+            rent.minimum_balance(spl_token::state::Account::LEN),
+            spl_token::state::Account::LEN as u64,
+            &spl_token::id()
+        ),
+        spl_token::instruction::initialize_account(&spl_token::id(), &account.pubkey(), mint, owner).unwrap(),
+    ], &[&account]).await;
+    account.pubkey()
+}
+
+// Written straight into place, as the tests do not load the associated token program
+async fn create_associated_token_account(context: &mut ProgramTestContext, mint: &Pubkey, owner: &Pubkey) -> Pubkey {
+    let address = get_associated_token_address(owner, mint);
+    let rent = context.banks_client.get_rent().await.unwrap();
+    let mut data = vec![0; spl_token::state::Account::LEN];
+    spl_token::state::Account {
+        mint: *mint,
+        owner: *owner,
+        state: spl_token::state::AccountState::Initialized,
+        ..Default::default()
+    }.pack_into_slice(&mut data);
+    context.set_account(&address, &AccountSharedData::from(Account {
+        lamports: rent.minimum_balance(data.len()),
+        data,
+        owner: spl_token::id(),
+        executable: false,
+        rent_epoch: 0,
+    }));
+    address
+}
+
+async fn mint_to(context: &mut ProgramTestContext, mint: &Pubkey, to: &Pubkey, amount: u64) {
+    let payer = context.payer.pubkey();
+    process(context, &[
+        spl_token::instruction::mint_to(&spl_token::id(), mint, to, &payer, &[], amount).unwrap(),
+    ], &[]).await;
+}
+
+async fn token_balance(context: &mut ProgramTestContext, address: &Pubkey) -> u64 {
+    let data = account_data(context, address).await;
+    spl_token::state::Account::unpack(&data).unwrap().amount
+}
+
+struct Farm {
+    manager: Pubkey,
+    deposit_mint: Pubkey,
+    reward_mint: Pubkey,
+}
+
+// Appoints a manager with a single crop
+async fn cultivate(context: &mut ProgramTestContext, params: &CropParams) -> Farm {
+    cultivate_with(context, params, &Penalty::default(), 0, 0).await
+}
+
+async fn cultivate_with(
+    context: &mut ProgramTestContext,
+    params: &CropParams,
+    penalty: &Penalty,
+    fee_share: u64,
+    cooldown: u64
+) -> Farm {
+    let manager = Keypair::new();
+    let payer = context.payer.pubkey();
+    process(context, &[
+        instructions::appoint(&manager.pubkey(), &payer, MIN_TIMELOCK_DELAY, 100_000_000),
+    ], &[&manager]).await;
+
+    let deposit_mint = create_mint(context).await;
+    let reward_mint = create_mint(context).await;
+    let deposit_treasury = Keypair::new();
+    let reward_treasury = Keypair::new();
+    process(context, &[
+        instructions::cultivate(
+            &manager.pubkey(),
+            0,
+            &payer,
+            &deposit_mint,
+            &reward_mint,
+            &deposit_treasury.pubkey(),
+            &reward_treasury.pubkey(),
+            true,
+            params,
+            penalty,
+            fee_share,
+            cooldown,
+            None
+        ),
+    ], &[&deposit_treasury, &reward_treasury]).await;
+
+    Farm {
+        manager: manager.pubkey(),
+        deposit_mint,
+        reward_mint,
+    }
+}
+
+#[tokio::test]
+async fn cultivates_crop() {
+    let mut context = start().await;
+    let params = CropParams {
+        deposit_fee: 10_000_000,
+        withdraw_fee: 20_000_000,
+        referral_fee: 0,
+        end_timestamp: u64::MAX,
+        reward_rate: 100,
+    };
+    let farm = cultivate(&mut context, &params).await;
+
+    let manager = state::decode_manager(&account_data(&mut context, &farm.manager).await).unwrap();
+    assert_eq!(manager.owner, context.payer.pubkey());
+    assert_eq!(manager.crops, 1);
+    assert_eq!(manager.rewarder_bump, pda::find_rewarder(&farm.manager).1);
+
+    let (crop_key, bump) = pda::find_crop(&farm.manager, 0);
+    let crop = state::decode_crop(&account_data(&mut context, &crop_key).await).unwrap();
+    assert_eq!(crop.manager, farm.manager);
+    assert_eq!(crop.deposit_mint, farm.deposit_mint);
+    assert_eq!(crop.reward_mint, farm.reward_mint);
+    assert_eq!(crop.deposit_fee, params.deposit_fee);
+    assert_eq!(crop.withdraw_fee, params.withdraw_fee);
+    assert_eq!(crop.reward_rate, params.reward_rate);
+    assert_eq!(crop.bump, bump);
+    assert_eq!(crop.id, 0);
+}
+
+#[tokio::test]
+async fn sows_and_uproots() {
+    let mut context = start().await;
+    let params = CropParams {
+        deposit_fee: 0,
+        withdraw_fee: 0,
+        referral_fee: 0,
+        end_timestamp: u64::MAX,
+        reward_rate: 0,
+    };
+    let farm = cultivate(&mut context, &params).await;
+    let farmer = context.payer.pubkey();
+
+    process(&mut context, &[instructions::till(&farm.manager, 0, &farmer)], &[]).await;
+
+    let deposit_account = create_token_account(&mut context, &farm.deposit_mint, &farmer).await;
+    let reward_account = create_token_account(&mut context, &farm.reward_mint, &farmer).await;
+    mint_to(&mut context, &farm.deposit_mint, &deposit_account, 1_000).await;
+
+    let (crop_key, _) = pda::find_crop(&farm.manager, 0);
+    let (plot_key, plot_bump) = pda::find_plot(&farm.manager, &farmer, 0);
+    let crop = state::decode_crop(&account_data(&mut context, &crop_key).await).unwrap();
+
+    process(&mut context, &[
+        instructions::sow(&crop, &farmer, &deposit_account, 1_000, None, None),
+    ], &[]).await;
+
+    let plot = state::decode_plot(&account_data(&mut context, &plot_key).await).unwrap();
+    assert_eq!(plot.amount, 1_000);
+    assert_eq!(plot.bump, plot_bump);
+    assert_eq!(token_balance(&mut context, &crop.deposit_treasury).await, 1_000);
+
+    process(&mut context, &[
+        instructions::uproot(&crop, &plot, &farmer, &deposit_account, &reward_account, 1_000, None),
+    ], &[]).await;
+
+    let plot = state::decode_plot(&account_data(&mut context, &plot_key).await).unwrap();
+    assert_eq!(plot.amount, 0);
+    assert_eq!(token_balance(&mut context, &deposit_account).await, 1_000);
+    let crop = state::decode_crop(&account_data(&mut context, &crop_key).await).unwrap();
+    assert_eq!(crop.total_deposited, 0);
+}
+
+#[tokio::test]
+async fn harvests_many_crops() {
+    let mut context = start().await;
+    let params = CropParams {
+        deposit_fee: 0,
+        withdraw_fee: 0,
+        referral_fee: 0,
+        end_timestamp: u64::MAX,
+        reward_rate: 100,
+    };
+    let farm = cultivate(&mut context, &params).await;
+    let farmer = context.payer.pubkey();
+    let crop = state::decode_crop(&account_data(&mut context, &pda::find_crop(&farm.manager, 0).0).await).unwrap();
+    let deposit_treasury = Keypair::new();
+    process(&mut context, &[
+        instructions::cultivate(
+            &farm.manager,
+            1,
+            &farmer,
+            &farm.deposit_mint,
+            &farm.reward_mint,
+            &deposit_treasury.pubkey(),
+            &crop.reward_treasury,
+            false,
+            &params,
+            &Penalty::default(),
+            0,
+            0,
+            None
+        ),
+    ], &[&deposit_treasury]).await;
+    let other_crop = state::decode_crop(&account_data(&mut context, &pda::find_crop(&farm.manager, 1).0).await).unwrap();
+
+    let deposit_account = create_token_account(&mut context, &farm.deposit_mint, &farmer).await;
+    let reward_account = create_token_account(&mut context, &farm.reward_mint, &farmer).await;
+    let funding_account = create_token_account(&mut context, &farm.reward_mint, &farmer).await;
+    mint_to(&mut context, &farm.deposit_mint, &deposit_account, 2_000).await;
+    mint_to(&mut context, &farm.reward_mint, &funding_account, 1_000_000).await;
+    process(&mut context, &[
+        instructions::fertilize(&crop, &farmer, &funding_account, 500_000),
+        instructions::fertilize(&other_crop, &farmer, &funding_account, 500_000),
+        instructions::till(&farm.manager, 0, &farmer),
+        instructions::till(&farm.manager, 1, &farmer),
+        instructions::sow(&crop, &farmer, &deposit_account, 1_000, None, None),
+        instructions::sow(&other_crop, &farmer, &deposit_account, 1_000, None, None),
+    ], &[]).await;
+    advance_clock(&mut context, 100).await;
+
+    let plot = state::decode_plot(&account_data(&mut context, &pda::find_plot(&farm.manager, &farmer, 0).0).await).unwrap();
+    let other_plot = state::decode_plot(&account_data(&mut context, &pda::find_plot(&farm.manager, &farmer, 1).0).await).unwrap();
+    process(&mut context, &[
+        instructions::harvest_many(&farm.manager, &farmer, &[
+            (crop.clone(), plot.clone(), reward_account, deposit_account),
+            (other_crop.clone(), other_plot.clone(), reward_account, deposit_account),
+        ]),
+    ], &[]).await;
+    assert!(token_balance(&mut context, &reward_account).await >= 2 * 100 * 100);
+    for crop_id in 0..2 {
+        let plot = state::decode_plot(&account_data(&mut context, &pda::find_plot(&farm.manager, &farmer, crop_id).0).await).unwrap();
+        assert_eq!(plot.amount, 1_000);
+        assert_eq!(plot.unclaimed, 0);
+    }
+
+    // Crops of another manager are turned away
+    let stranger = cultivate(&mut context, &params).await;
+    let stranger_crop = state::decode_crop(&account_data(&mut context, &pda::find_crop(&stranger.manager, 0).0).await).unwrap();
+    process(&mut context, &[instructions::till(&stranger.manager, 0, &farmer)], &[]).await;
+    assert!(!try_process(&mut context, &[
+        instructions::harvest_many(&farm.manager, &farmer, &[(stranger_crop, plot, reward_account, deposit_account)]),
+    ], &[]).await);
+}
+
+#[tokio::test]
+async fn keeps_plots_with_keepers() {
+    let mut context = start().await;
+    let params = CropParams {
+        deposit_fee: 0,
+        withdraw_fee: 0,
+        referral_fee: 0,
+        end_timestamp: u64::MAX,
+        reward_rate: 100,
+    };
+    let farm = cultivate(&mut context, &params).await;
+    let farmer = context.payer.pubkey();
+    let keeper = Keypair::new();
+    let crop = state::decode_crop(&account_data(&mut context, &pda::find_crop(&farm.manager, 0).0).await).unwrap();
+
+    // A second crop paying rewards in its deposit mint, so they can be compounded
+    let deposit_treasury = Keypair::new();
+    let reward_treasury = Keypair::new();
+    process(&mut context, &[
+        instructions::cultivate(
+            &farm.manager,
+            1,
+            &farmer,
+            &farm.deposit_mint,
+            &farm.deposit_mint,
+            &deposit_treasury.pubkey(),
+            &reward_treasury.pubkey(),
+            true,
+            &CropParams { deposit_fee: 100_000_000, ..params },
+            &Penalty { max: 100_000_000, period: 1_000_000 },
+            0,
+            0,
+            None
+        ),
+    ], &[&deposit_treasury, &reward_treasury]).await;
+    let compounding_crop = state::decode_crop(&account_data(&mut context, &pda::find_crop(&farm.manager, 1).0).await).unwrap();
+
+    let deposit_account = create_token_account(&mut context, &farm.deposit_mint, &farmer).await;
+    let reward_account = create_token_account(&mut context, &farm.reward_mint, &farmer).await;
+    let keeper_account = create_token_account(&mut context, &farm.reward_mint, &keeper.pubkey()).await;
+    let funding_account = create_token_account(&mut context, &farm.reward_mint, &farmer).await;
+    mint_to(&mut context, &farm.deposit_mint, &deposit_account, 1_002_000).await;
+    mint_to(&mut context, &farm.reward_mint, &funding_account, 1_000_000).await;
+    process(&mut context, &[
+        instructions::fertilize(&crop, &farmer, &funding_account, 1_000_000),
+        instructions::fertilize(&compounding_crop, &farmer, &deposit_account, 1_000_000),
+        instructions::till(&farm.manager, 0, &farmer),
+        instructions::till(&farm.manager, 1, &farmer),
+        instructions::sow(&crop, &farmer, &deposit_account, 1_000, None, None),
+        instructions::sow(&compounding_crop, &farmer, &deposit_account, 1_000, None, None),
+    ], &[]).await;
+    advance_clock(&mut context, 100).await;
+    let plot = state::decode_plot(&account_data(&mut context, &pda::find_plot(&farm.manager, &farmer, 0).0).await).unwrap();
+    let compounding_plot = state::decode_plot(&account_data(&mut context, &pda::find_plot(&farm.manager, &farmer, 1).0).await).unwrap();
+
+    // Nobody but the farmer acts on a plot without a keeper
+    assert!(!try_process(&mut context, &[
+        instructions::glean(&compounding_crop, &compounding_plot, &farmer, &keeper.pubkey(), &deposit_account, &deposit_account),
+    ], &[&keeper]).await);
+
+    process(&mut context, &[
+        instructions::hire(&crop, &farmer, &keeper.pubkey(), state::KeeperScope::Harvest),
+        instructions::hire(&compounding_crop, &farmer, &keeper.pubkey(), state::KeeperScope::Harvest),
+    ], &[]).await;
+
+    // Rewards only go to the farmer's own accounts
+    assert!(!try_process(&mut context, &[
+        instructions::glean(&crop, &plot, &farmer, &keeper.pubkey(), &keeper_account, &deposit_account),
+    ], &[&keeper]).await);
+    process(&mut context, &[
+        instructions::glean(&crop, &plot, &farmer, &keeper.pubkey(), &reward_account, &deposit_account),
+    ], &[&keeper]).await;
+    assert!(token_balance(&mut context, &reward_account).await >= 100 * 100);
+
+    // Compounding needs the wider scope
+    assert!(!try_process(&mut context, &[
+        instructions::compound(&compounding_crop, &compounding_plot, &farmer, &keeper.pubkey()),
+    ], &[&keeper]).await);
+    process(&mut context, &[
+        instructions::hire(&compounding_crop, &farmer, &keeper.pubkey(), state::KeeperScope::Compound),
+        instructions::compound(&compounding_crop, &compounding_plot, &farmer, &keeper.pubkey()),
+    ], &[&keeper]).await;
+
+    // Restaked without the deposit fee and without restarting the penalty
+    let compounded_plot = state::decode_plot(&account_data(&mut context, &pda::find_plot(&farm.manager, &farmer, 1).0).await).unwrap();
+    let compounding_crop = state::decode_crop(&account_data(&mut context, &pda::find_crop(&farm.manager, 1).0).await).unwrap();
+    assert!(compounded_plot.amount > 900);
+    assert_eq!(compounded_plot.deposit_timestamp, compounding_plot.deposit_timestamp);
+    assert_eq!(compounding_crop.fees, 100);
+    assert_eq!(
+        token_balance(&mut context, &compounding_crop.deposit_treasury).await,
+        compounded_plot.amount + compounding_crop.fees
+    );
+
+    // Dismissed keepers are turned away while the farmer still can glean
+    let other_reward_account = create_token_account(&mut context, &farm.reward_mint, &farmer).await;
+    process(&mut context, &[instructions::dismiss(&crop, &farmer)], &[]).await;
+    assert!(!try_process(&mut context, &[
+        instructions::glean(&crop, &plot, &farmer, &keeper.pubkey(), &other_reward_account, &deposit_account),
+    ], &[&keeper]).await);
+    process(&mut context, &[
+        instructions::glean(&crop, &plot, &farmer, &farmer, &other_reward_account, &deposit_account),
+    ], &[]).await;
+}
+
+#[tokio::test]
+async fn reckons_transferred_receipts() {
+    let mut context = start().await;
+    let params = CropParams {
+        deposit_fee: 0,
+        withdraw_fee: 0,
+        referral_fee: 0,
+        end_timestamp: u64::MAX,
+        reward_rate: 0,
+    };
+    let farm = cultivate(&mut context, &params).await;
+    let payer = context.payer.pubkey();
+    let other = Keypair::new();
+    let (crop_key, _) = pda::find_crop(&farm.manager, 0);
+    let crop = state::decode_crop(&account_data(&mut context, &crop_key).await).unwrap();
+    process(&mut context, &[instructions::certify(&crop, &payer)], &[]).await;
+    let crop = state::decode_crop(&account_data(&mut context, &crop_key).await).unwrap();
+
+    let deposit_account = create_token_account(&mut context, &farm.deposit_mint, &payer).await;
+    let payer_receipts = create_associated_token_account(&mut context, &crop.receipt_mint, &payer).await;
+    let other_receipts = create_associated_token_account(&mut context, &crop.receipt_mint, &other.pubkey()).await;
+    mint_to(&mut context, &farm.deposit_mint, &deposit_account, 1_000).await;
+    process(&mut context, &[
+        system_instruction::transfer(&payer, &other.pubkey(), 1_000_000_000),
+        instructions::till(&farm.manager, 0, &payer),
+        instructions::till(&farm.manager, 0, &other.pubkey()),
+        instructions::sow(&crop, &payer, &deposit_account, 1_000, None, Some(payer_receipts)),
+        spl_token::instruction::transfer(&spl_token::id(), &payer_receipts, &other_receipts, &payer, &[], 400).unwrap(),
+    ], &[&other]).await;
+
+    // The receiver cannot claim receipts still counted in the sender's plot, but can settle it
+    assert!(!try_process(&mut context, &[
+        instructions::reckon(&crop, &other.pubkey(), &other.pubkey()),
+    ], &[&other]).await);
+    process(&mut context, &[
+        instructions::reckon(&crop, &payer, &other.pubkey()),
+        instructions::reckon(&crop, &other.pubkey(), &other.pubkey()),
+    ], &[&other]).await;
+    let plot = state::decode_plot(&account_data(&mut context, &pda::find_plot(&farm.manager, &payer, 0).0).await).unwrap();
+    let other_plot = state::decode_plot(&account_data(&mut context, &pda::find_plot(&farm.manager, &other.pubkey(), 0).0).await).unwrap();
+    assert_eq!(plot.amount, 600);
+    assert_eq!(other_plot.amount, 400);
+
+    // Only the farmer brings their own plot up
+    process(&mut context, &[
+        spl_token::instruction::transfer(&spl_token::id(), &other_receipts, &payer_receipts, &other.pubkey(), &[], 100).unwrap(),
+        instructions::reckon(&crop, &other.pubkey(), &other.pubkey()),
+    ], &[&other]).await;
+    assert!(!try_process(&mut context, &[
+        instructions::reckon(&crop, &payer, &other.pubkey()),
+    ], &[&other]).await);
+    process(&mut context, &[instructions::reckon(&crop, &payer, &payer)], &[]).await;
+    let plot = state::decode_plot(&account_data(&mut context, &pda::find_plot(&farm.manager, &payer, 0).0).await).unwrap();
+    assert_eq!(plot.amount, 700);
+}
+
+#[tokio::test]
+async fn migrates_stake_between_crops() {
+    let mut context = start().await;
+    let params = CropParams {
+        deposit_fee: 100_000_000,
+        withdraw_fee: 100_000_000,
+        referral_fee: 0,
+        end_timestamp: u64::MAX,
+        reward_rate: 0,
+    };
+    let farm = cultivate(&mut context, &params).await;
+    let farmer = context.payer.pubkey();
+    let crop = state::decode_crop(&account_data(&mut context, &pda::find_crop(&farm.manager, 0).0).await).unwrap();
+    let deposit_treasury = Keypair::new();
+    process(&mut context, &[
+        instructions::cultivate(
+            &farm.manager,
+            1,
+            &farmer,
+            &farm.deposit_mint,
+            &farm.reward_mint,
+            &deposit_treasury.pubkey(),
+            &crop.reward_treasury,
+            false,
+            &params,
+            &Penalty::default(),
+            0,
+            0,
+            None
+        ),
+    ], &[&deposit_treasury]).await;
+    let successor = state::decode_crop(&account_data(&mut context, &pda::find_crop(&farm.manager, 1).0).await).unwrap();
+
+    let deposit_account = create_token_account(&mut context, &farm.deposit_mint, &farmer).await;
+    mint_to(&mut context, &farm.deposit_mint, &deposit_account, 2_000).await;
+    process(&mut context, &[
+        instructions::till(&farm.manager, 0, &farmer),
+        instructions::till(&farm.manager, 1, &farmer),
+        instructions::sow(&crop, &farmer, &deposit_account, 2_000, None, None),
+    ], &[]).await;
+
+    // Without a route both fees apply
+    process(&mut context, &[
+        instructions::migrate_stake(&crop, &successor, &farmer, 900),
+    ], &[]).await;
+    let plot = state::decode_plot(&account_data(&mut context, &pda::find_plot(&farm.manager, &farmer, 1).0).await).unwrap();
+    assert_eq!(plot.amount, 729);
+    assert_eq!(token_balance(&mut context, &successor.deposit_treasury).await, 810);
+
+    process(&mut context, &[
+        instructions::chart(&farm.manager, 0, 1, &farmer),
+        instructions::migrate_stake(&crop, &successor, &farmer, 900),
+    ], &[]).await;
+    let plot = state::decode_plot(&account_data(&mut context, &pda::find_plot(&farm.manager, &farmer, 1).0).await).unwrap();
+    assert_eq!(plot.amount, 729 + 900);
+    let source = state::decode_plot(&account_data(&mut context, &pda::find_plot(&farm.manager, &farmer, 0).0).await).unwrap();
+    assert_eq!(source.amount, 0);
+    let crop = state::decode_crop(&account_data(&mut context, &pda::find_crop(&farm.manager, 0).0).await).unwrap();
+    assert_eq!(crop.fees, 200 + 90);
+
+    // Routes only go one way
+    process(&mut context, &[
+        instructions::migrate_stake(&successor, &crop, &farmer, 1_000),
+    ], &[]).await;
+    let source = state::decode_plot(&account_data(&mut context, &pda::find_plot(&farm.manager, &farmer, 0).0).await).unwrap();
+    assert_eq!(source.amount, 810);
+
+    process(&mut context, &[instructions::unchart(&farm.manager, 0, 1, &farmer)], &[]).await;
+    let route = pda::find_route(&pda::find_crop(&farm.manager, 0).0, &pda::find_crop(&farm.manager, 1).0).0;
+    assert!(context.banks_client.get_account(route).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn queues_and_applies_updates() {
+    let mut context = start().await;
+    let params = CropParams {
+        deposit_fee: 0,
+        withdraw_fee: 0,
+        referral_fee: 0,
+        end_timestamp: u64::MAX,
+        reward_rate: 0,
+    };
+    let farm = cultivate(&mut context, &params).await;
+    let operator = Keypair::new();
+    let owner = context.payer.pubkey();
+
+    process(&mut context, &[
+        instructions::grant(&farm.manager, &owner, Role::Operator, &operator.pubkey()),
+    ], &[]).await;
+
+    let clock: Clock = context.banks_client.get_sysvar().await.unwrap();
+    let update = CropParams { reward_rate: 50, ..params };
+    let effective_timestamp = clock.unix_timestamp as u64 + MIN_TIMELOCK_DELAY;
+    let recultivate = instructions::recultivate(&farm.manager, 0, &operator.pubkey(), &update, &Penalty::default(), 0, 0, effective_timestamp);
+
+    // Nothing can be applied before the delay is over
+    assert!(!try_process(&mut context, &[
+        recultivate.clone(),
+        instructions::ripen(&farm.manager, 0),
+    ], &[&operator]).await);
+
+    process(&mut context, &[recultivate], &[&operator]).await;
+    advance_clock(&mut context, MIN_TIMELOCK_DELAY as i64).await;
+    process(&mut context, &[instructions::ripen(&farm.manager, 0)], &[]).await;
+
+    let (crop_key, _) = pda::find_crop(&farm.manager, 0);
+    let crop = state::decode_crop(&account_data(&mut context, &crop_key).await).unwrap();
+    assert_eq!(crop.reward_rate, 50);
+    assert_eq!(crop.pending_timestamp, 0);
+
+    // Pruning drops every part of the queued update
+    let clock: Clock = context.banks_client.get_sysvar().await.unwrap();
+    process(&mut context, &[
+        instructions::recultivate(
+            &farm.manager,
+            0,
+            &operator.pubkey(),
+            &update,
+            &Penalty { max: 1_000, period: 100 },
+            1_000,
+            100,
+            clock.unix_timestamp as u64 + MIN_TIMELOCK_DELAY
+        ),
+        instructions::prune(&farm.manager, 0, &operator.pubkey()),
+    ], &[&operator]).await;
+    let crop = state::decode_crop(&account_data(&mut context, &crop_key).await).unwrap();
+    assert_eq!(crop.pending_timestamp, 0);
+    assert_eq!(crop.pending_penalty.max, 0);
+    assert_eq!(crop.pending_penalty.period, 0);
+    assert_eq!(crop.pending_fee_share, 0);
+    assert_eq!(crop.pending_cooldown, 0);
+}
+
+#[tokio::test]
+async fn labels_crop() {
+    let mut context = start().await;
+    let params = CropParams {
+        deposit_fee: 0,
+        withdraw_fee: 0,
+        referral_fee: 0,
+        end_timestamp: u64::MAX,
+        reward_rate: 0,
+    };
+    let farm = cultivate(&mut context, &params).await;
+    let operator = context.payer.pubkey();
+    let (crop_key, _) = pda::find_crop(&farm.manager, 0);
+    let (metadata_key, bump) = pda::find_metadata(&crop_key);
+
+    process(&mut context, &[
+        instructions::label(&farm.manager, 0, &operator, "USDC-SOL LP".to_string(), String::new(), vec![]),
+    ], &[]).await;
+    process(&mut context, &[
+        instructions::label(
+            &farm.manager,
+            0,
+            &operator,
+            "USDC-SOL LP farm".to_string(),
+            "https://example.com/farm.json".to_string(),
+            vec!["lp".to_string(), "stable".to_string()]
+        ),
+    ], &[]).await;
+
+    let metadata = state::decode_metadata(&account_data(&mut context, &metadata_key).await).unwrap();
+    assert_eq!(metadata.crop, crop_key);
+    assert_eq!(metadata.name, "USDC-SOL LP farm");
+    assert_eq!(metadata.uri, "https://example.com/farm.json");
+    assert_eq!(metadata.tags, vec!["lp", "stable"]);
+    assert_eq!(metadata.bump, bump);
+
+    let stranger = Keypair::new();
+    assert!(!try_process(&mut context, &[
+        instructions::label(&farm.manager, 0, &stranger.pubkey(), "Mine".to_string(), String::new(), vec![]),
+    ], &[&stranger]).await);
+    assert!(!try_process(&mut context, &[
+        instructions::label(&farm.manager, 0, &operator, "x".repeat(33), String::new(), vec![]),
+    ], &[]).await);
+}
+
+#[tokio::test]
+async fn labels_crop_when_cultivated() {
+    let mut context = start().await;
+    let params = CropParams {
+        deposit_fee: 0,
+        withdraw_fee: 0,
+        referral_fee: 0,
+        end_timestamp: u64::MAX,
+        reward_rate: 0,
+    };
+    let farm = cultivate(&mut context, &params).await;
+    let operator = context.payer.pubkey();
+    let crop = state::decode_crop(&account_data(&mut context, &pda::find_crop(&farm.manager, 0).0).await).unwrap();
+    let metadata = MetadataParams {
+        name: "USDC-SOL LP farm".to_string(),
+        uri: "https://example.com/farm.json".to_string(),
+        tags: vec!["lp".to_string()],
+    };
+    let cultivate = |deposit_treasury: &Keypair, metadata: MetadataParams| instructions::cultivate(
+        &farm.manager,
+        1,
+        &operator,
+        &farm.deposit_mint,
+        &farm.reward_mint,
+        &deposit_treasury.pubkey(),
+        &crop.reward_treasury,
+        false,
+        &params,
+        &Penalty::default(),
+        0,
+        0,
+        Some(metadata)
+    );
+
+    // Metadata is checked like it is when labelling
+    let deposit_treasury = Keypair::new();
+    let too_long = MetadataParams { name: "x".repeat(33), ..metadata.clone() };
+    assert!(!try_process(&mut context, &[cultivate(&deposit_treasury, too_long)], &[&deposit_treasury]).await);
+
+    process(&mut context, &[cultivate(&deposit_treasury, metadata)], &[&deposit_treasury]).await;
+
+    let crop_key = pda::find_crop(&farm.manager, 1).0;
+    let (metadata_key, bump) = pda::find_metadata(&crop_key);
+    let metadata = state::decode_metadata(&account_data(&mut context, &metadata_key).await).unwrap();
+    assert_eq!(metadata.crop, crop_key);
+    assert_eq!(metadata.name, "USDC-SOL LP farm");
+    assert_eq!(metadata.uri, "https://example.com/farm.json");
+    assert_eq!(metadata.tags, vec!["lp"]);
+    assert_eq!(metadata.bump, bump);
+
+    // Label still updates it later
+    process(&mut context, &[
+        instructions::label(&farm.manager, 1, &operator, "Renamed".to_string(), String::new(), vec![]),
+    ], &[]).await;
+    let metadata = state::decode_metadata(&account_data(&mut context, &metadata_key).await).unwrap();
+    assert_eq!(metadata.name, "Renamed");
+}
+
+#[tokio::test]
+async fn apportions_emissions() {
+    let mut context = start().await;
+    let params = CropParams {
+        deposit_fee: 0,
+        withdraw_fee: 0,
+        referral_fee: 0,
+        end_timestamp: u64::MAX,
+        reward_rate: 0,
+    };
+    let farm = cultivate(&mut context, &params).await;
+    let operator = context.payer.pubkey();
+    let deposit_treasury = Keypair::new();
+    let (crop_key, _) = pda::find_crop(&farm.manager, 0);
+    let reward_treasury = state::decode_crop(&account_data(&mut context, &crop_key).await).unwrap().reward_treasury;
+    process(&mut context, &[
+        instructions::cultivate(
+            &farm.manager,
+            1,
+            &operator,
+            &farm.deposit_mint,
+            &farm.reward_mint,
+            &deposit_treasury.pubkey(),
+            &reward_treasury,
+            false,
+            &params,
+            &Penalty::default(),
+            0,
+            0,
+            None
+        ),
+    ], &[&deposit_treasury]).await;
+
+    let clock: Clock = context.banks_client.get_sysvar().await.unwrap();
+    let now = clock.unix_timestamp as u64;
+    let effective_timestamp = now + MIN_TIMELOCK_DELAY;
+
+    // Apportionments wait out the timelock, and can only be continued once begun from crop 0
+    assert!(!try_process(&mut context, &[
+        instructions::apportion(&farm.manager, &operator, 100, 0, vec![1], now),
+    ], &[]).await);
+    assert!(!try_process(&mut context, &[
+        instructions::apportion(&farm.manager, &operator, 100, 1, vec![3], effective_timestamp),
+    ], &[]).await);
+
+    // One crop at a time, as a manager with many crops would
+    process(&mut context, &[
+        instructions::apportion(&farm.manager, &operator, 100, 0, vec![1], effective_timestamp),
+    ], &[]).await;
+    assert!(!try_process(&mut context, &[
+        instructions::apportion(&farm.manager, &operator, 50, 1, vec![3], effective_timestamp),
+    ], &[]).await);
+    process(&mut context, &[
+        instructions::apportion(&farm.manager, &operator, 100, 1, vec![3], effective_timestamp),
+    ], &[]).await;
+
+    let manager = state::decode_manager(&account_data(&mut context, &farm.manager).await).unwrap();
+    assert_eq!(manager.emission_rate, 0);
+    assert_eq!(manager.pending_total_alloc_points, 4);
+    assert_eq!(manager.apportioned_crops, 2);
+    assert!(!try_process(&mut context, &[instructions::rotate(&farm.manager, 0, 2)], &[]).await);
+
+    // Rotated one crop at a time, the manager only switches over with the last one
+    advance_clock(&mut context, MIN_TIMELOCK_DELAY as i64).await;
+    process(&mut context, &[instructions::rotate(&farm.manager, 0, 1)], &[]).await;
+    let manager = state::decode_manager(&account_data(&mut context, &farm.manager).await).unwrap();
+    assert_eq!(manager.emission_rate, 0);
+    assert_eq!(manager.rotated_crops, 1);
+    assert!(!try_process(&mut context, &[
+        instructions::apportion(&farm.manager, &operator, 10, 0, vec![1, 1], effective_timestamp + MIN_TIMELOCK_DELAY),
+    ], &[]).await);
+    process(&mut context, &[instructions::rotate(&farm.manager, 1, 1)], &[]).await;
+
+    let manager = state::decode_manager(&account_data(&mut context, &farm.manager).await).unwrap();
+    assert_eq!(manager.emission_rate, 100);
+    assert_eq!(manager.total_alloc_points, 4);
+    assert_eq!(manager.pending_emission_rate, 0);
+    assert_eq!(manager.pending_total_alloc_points, 0);
+    assert_eq!(manager.apportioned_crops, 0);
+    assert_eq!(manager.pending_apportion_timestamp, 0);
+    assert_eq!(manager.rotated_crops, 0);
+    for (crop_id, (points, rate)) in [(1, 25), (3, 75)].iter().enumerate() {
+        let crop_key = pda::find_crop(&farm.manager, crop_id as u64).0;
+        let crop = state::decode_crop(&account_data(&mut context, &crop_key).await).unwrap();
+        assert_eq!(crop.alloc_points, *points);
+        assert_eq!(crop.reward_rate, *rate);
+        assert!(!crop.pending_apportion);
+    }
+
+    // New crops get their rate from points, not from cultivate
+    let deposit_treasury = Keypair::new();
+    assert!(!try_process(&mut context, &[
+        instructions::cultivate(
+            &farm.manager,
+            2,
+            &operator,
+            &farm.deposit_mint,
+            &farm.reward_mint,
+            &deposit_treasury.pubkey(),
+            &reward_treasury,
+            false,
+            &CropParams { reward_rate: 10, ..params },
+            &Penalty::default(),
+            0,
+            0,
+            None
+        ),
+    ], &[&deposit_treasury]).await);
+}
+
+async fn advance_clock(context: &mut ProgramTestContext, seconds: i64) {
+    let mut clock: Clock = context.banks_client.get_sysvar().await.unwrap();
+    clock.unix_timestamp += seconds;
+    context.set_sysvar(&clock);
+}
+
+#[tokio::test]
+async fn caps_payouts_at_budget() {
+    let mut context = start().await;
+    let params = CropParams {
+        deposit_fee: 0,
+        withdraw_fee: 0,
+        referral_fee: 0,
+        end_timestamp: u64::MAX,
+        reward_rate: 100,
+    };
+    let farm = cultivate(&mut context, &params).await;
+    let payer = context.payer.pubkey();
+    let (crop_key, _) = pda::find_crop(&farm.manager, 0);
+    let (plot_key, _) = pda::find_plot(&farm.manager, &payer, 0);
+    let crop = state::decode_crop(&account_data(&mut context, &crop_key).await).unwrap();
+
+    // A second crop sharing the reward treasury, holding budget the first one can be given
+    let deposit_treasury = Keypair::new();
+    process(&mut context, &[
+        instructions::cultivate(
+            &farm.manager,
+            1,
+            &payer,
+            &farm.deposit_mint,
+            &farm.reward_mint,
+            &deposit_treasury.pubkey(),
+            &crop.reward_treasury,
+            false,
+            &CropParams { reward_rate: 0, ..params },
+            &Penalty::default(),
+            0,
+            0,
+            None
+        ),
+    ], &[&deposit_treasury]).await;
+    let other_crop = state::decode_crop(&account_data(&mut context, &pda::find_crop(&farm.manager, 1).0).await).unwrap();
+
+    let funding_account = create_token_account(&mut context, &farm.reward_mint, &payer).await;
+    let reward_account = create_token_account(&mut context, &farm.reward_mint, &payer).await;
+    let deposit_account = create_token_account(&mut context, &farm.deposit_mint, &payer).await;
+    mint_to(&mut context, &farm.reward_mint, &funding_account, 1_000_000).await;
+    mint_to(&mut context, &farm.deposit_mint, &deposit_account, 1_000).await;
+
+    process(&mut context, &[
+        instructions::till(&farm.manager, 0, &payer),
+        instructions::sow(&crop, &payer, &deposit_account, 1_000, None, None),
+        instructions::fertilize(&crop, &payer, &funding_account, 300),
+        instructions::fertilize(&other_crop, &payer, &funding_account, 100_000),
+    ], &[]).await;
+    advance_clock(&mut context, 100).await;
+
+    let plot = state::decode_plot(&account_data(&mut context, &plot_key).await).unwrap();
+    process(&mut context, &[
+        instructions::uproot(&crop, &plot, &payer, &deposit_account, &reward_account, 0, None),
+    ], &[]).await;
+
+    // The rest of what was earned waits on the plot
+    assert_eq!(token_balance(&mut context, &reward_account).await, 300);
+    let crop = state::decode_crop(&account_data(&mut context, &crop_key).await).unwrap();
+    assert_eq!(crop.rewards_paid, 300);
+    let plot = state::decode_plot(&account_data(&mut context, &plot_key).await).unwrap();
+    assert!(plot.unclaimed > 0);
+    let unclaimed = plot.unclaimed;
+
+    assert!(!try_process(&mut context, &[
+        instructions::reallot(&farm.manager, 0, 1, &payer, 1),
+    ], &[]).await);
+    process(&mut context, &[
+        instructions::reallot(&farm.manager, 1, 0, &payer, 100_000),
+        instructions::uproot(&crop, &plot, &payer, &deposit_account, &reward_account, 1_000, None),
+    ], &[]).await;
+    assert!(token_balance(&mut context, &reward_account).await >= 300 + unclaimed);
+    let other_crop = state::decode_crop(&account_data(&mut context, &pda::find_crop(&farm.manager, 1).0).await).unwrap();
+    assert_eq!(other_crop.reward_budget, 0);
+}
+
+#[tokio::test]
+async fn irrigates_reward_periods() {
+    let mut context = start().await;
+    let params = CropParams {
+        deposit_fee: 0,
+        withdraw_fee: 0,
+        referral_fee: 0,
+        end_timestamp: u64::MAX,
+        reward_rate: 0,
+    };
+    let farm = cultivate(&mut context, &params).await;
+    let operator = context.payer.pubkey();
+    let (crop_key, _) = pda::find_crop(&farm.manager, 0);
+    let crop = state::decode_crop(&account_data(&mut context, &crop_key).await).unwrap();
+    let funding_account = create_token_account(&mut context, &farm.reward_mint, &operator).await;
+    mint_to(&mut context, &farm.reward_mint, &funding_account, 20_000).await;
+
+    process(&mut context, &[
+        instructions::irrigate(&crop, &operator, &funding_account, 10_000, 100),
+    ], &[]).await;
+    let clock: Clock = context.banks_client.get_sysvar().await.unwrap();
+    let crop = state::decode_crop(&account_data(&mut context, &crop_key).await).unwrap();
+    assert_eq!(crop.reward_rate, 100);
+    assert_eq!(crop.end_timestamp, clock.unix_timestamp as u64 + 100);
+    assert_eq!(token_balance(&mut context, &crop.reward_treasury).await, 10_000);
+
+    // Half way through, what is left of the first period rolls into the second
+    advance_clock(&mut context, 50).await;
+    process(&mut context, &[
+        instructions::irrigate(&crop, &operator, &funding_account, 5_000, 200),
+    ], &[]).await;
+    let clock: Clock = context.banks_client.get_sysvar().await.unwrap();
+    let leftover = (crop.end_timestamp - clock.unix_timestamp as u64) * 100;
+    let crop = state::decode_crop(&account_data(&mut context, &crop_key).await).unwrap();
+    assert_eq!(crop.reward_rate, (5_000 + leftover) / 200);
+    assert_eq!(crop.end_timestamp, clock.unix_timestamp as u64 + 200);
+
+    assert!(!try_process(&mut context, &[
+        instructions::irrigate(&crop, &operator, &funding_account, 5_000, 0),
+    ], &[]).await);
+    assert!(!try_process(&mut context, &[
+        instructions::irrigate(&crop, &operator, &funding_account, 0, 100),
+    ], &[]).await);
+
+    // A queued update would overwrite the new period when it ripens
+    let clock: Clock = context.banks_client.get_sysvar().await.unwrap();
+    let effective_timestamp = clock.unix_timestamp as u64 + MIN_TIMELOCK_DELAY;
+    process(&mut context, &[
+        instructions::recultivate(&farm.manager, 0, &operator, &params, &Penalty::default(), 0, 0, effective_timestamp),
+    ], &[]).await;
+    assert!(!try_process(&mut context, &[
+        instructions::irrigate(&crop, &operator, &funding_account, 4_000, 100),
+    ], &[]).await);
+    process(&mut context, &[
+        instructions::prune(&farm.manager, 0, &operator),
+        instructions::irrigate(&crop, &operator, &funding_account, 5_000, 100),
+    ], &[]).await;
+    assert_eq!(token_balance(&mut context, &crop.reward_treasury).await, 20_000);
+}
+
+#[tokio::test]
+async fn tends_idle_crops() {
+    let mut context = start().await;
+    let params = CropParams {
+        deposit_fee: 0,
+        withdraw_fee: 0,
+        referral_fee: 0,
+        end_timestamp: u64::MAX,
+        reward_rate: 100,
+    };
+    let farm = cultivate(&mut context, &params).await;
+    let farmer = context.payer.pubkey();
+    let (crop_key, _) = pda::find_crop(&farm.manager, 0);
+    let crop = state::decode_crop(&account_data(&mut context, &crop_key).await).unwrap();
+    let deposit_account = create_token_account(&mut context, &farm.deposit_mint, &farmer).await;
+    mint_to(&mut context, &farm.deposit_mint, &deposit_account, 1_000).await;
+    process(&mut context, &[
+        instructions::till(&farm.manager, 0, &farmer),
+        instructions::sow(&crop, &farmer, &deposit_account, 1_000, None, None),
+    ], &[]).await;
+
+    advance_clock(&mut context, 100).await;
+    let crop = state::decode_crop(&account_data(&mut context, &crop_key).await).unwrap();
+    let clock: Clock = context.banks_client.get_sysvar().await.unwrap();
+    let now = clock.unix_timestamp as u64;
+    let projected = state::project_crop(&crop, now).unwrap();
+    assert!(projected.rewards_per_share > crop.rewards_per_share);
+
+    // Anyone can tend, not just the farm's own roles
+    let stranger = Keypair::new();
+    process(&mut context, &[
+        system_instruction::transfer(&farmer, &stranger.pubkey(), 1_000_000_000),
+    ], &[]).await;
+    let transaction = Transaction::new_signed_with_payer(
+        &[instructions::tend(&crop)],
+        Some(&stranger.pubkey()),
+        &[&stranger],
+        context.last_blockhash
+    );
+    context.banks_client.process_transaction(transaction).await.unwrap();
+
+    let crop = state::decode_crop(&account_data(&mut context, &crop_key).await).unwrap();
+    assert_eq!(crop.rewards_per_share, projected.rewards_per_share);
+    assert_eq!(crop.previous_reward_timestamp, now);
+}
+
+#[tokio::test]
+async fn cools_down_withdrawals() {
+    let mut context = start().await;
+    let params = CropParams {
+        deposit_fee: 0,
+        withdraw_fee: 0,
+        referral_fee: 0,
+        end_timestamp: u64::MAX,
+        reward_rate: 0,
+    };
+    let farm = cultivate_with(&mut context, &params, &Penalty::default(), 0, 100).await;
+    let farmer = context.payer.pubkey();
+    let (crop_key, _) = pda::find_crop(&farm.manager, 0);
+    let (plot_key, _) = pda::find_plot(&farm.manager, &farmer, 0);
+    let crop = state::decode_crop(&account_data(&mut context, &crop_key).await).unwrap();
+    let deposit_account = create_token_account(&mut context, &farm.deposit_mint, &farmer).await;
+    let reward_account = create_token_account(&mut context, &farm.reward_mint, &farmer).await;
+    mint_to(&mut context, &farm.deposit_mint, &deposit_account, 1_000).await;
+    process(&mut context, &[
+        instructions::till(&farm.manager, 0, &farmer),
+        instructions::sow(&crop, &farmer, &deposit_account, 1_000, None, None),
+    ], &[]).await;
+
+    // Nothing comes out without a request
+    let plot = state::decode_plot(&account_data(&mut context, &plot_key).await).unwrap();
+    assert!(!try_process(&mut context, &[
+        instructions::uproot(&crop, &plot, &farmer, &deposit_account, &reward_account, 500, None),
+    ], &[]).await);
+
+    process(&mut context, &[instructions::request_uproot(&crop, &farmer, 400)], &[]).await;
+    let plot = state::decode_plot(&account_data(&mut context, &plot_key).await).unwrap();
+    assert_eq!(plot.amount, 600);
+    assert_eq!(plot.unbonding_amount, 400);
+    let crop = state::decode_crop(&account_data(&mut context, &crop_key).await).unwrap();
+    assert_eq!(crop.total_deposited, 600);
+    assert!(!try_process(&mut context, &[
+        instructions::uproot(&crop, &plot, &farmer, &deposit_account, &reward_account, 300, None),
+    ], &[]).await);
+
+    process(&mut context, &[instructions::cancel_uproot(&crop, &farmer)], &[]).await;
+    let plot = state::decode_plot(&account_data(&mut context, &plot_key).await).unwrap();
+    assert_eq!(plot.amount, 1_000);
+    assert_eq!(plot.unbonding_amount, 0);
+
+    process(&mut context, &[instructions::request_uproot(&crop, &farmer, 250)], &[]).await;
+    advance_clock(&mut context, 100).await;
+    process(&mut context, &[
+        instructions::uproot(&crop, &plot, &farmer, &deposit_account, &reward_account, 250, None),
+    ], &[]).await;
+    assert_eq!(token_balance(&mut context, &deposit_account).await, 250);
+    let plot = state::decode_plot(&account_data(&mut context, &plot_key).await).unwrap();
+    assert_eq!(plot.amount, 750);
+    assert_eq!(plot.unbonding_amount, 0);
+
+    // Stake still cooling down when the cooldown is turned off comes out right away
+    let clock: Clock = context.banks_client.get_sysvar().await.unwrap();
+    let effective_timestamp = clock.unix_timestamp as u64 + MIN_TIMELOCK_DELAY;
+    process(&mut context, &[
+        instructions::recultivate(&farm.manager, 0, &farmer, &params, &Penalty::default(), 0, 0, effective_timestamp),
+    ], &[]).await;
+    advance_clock(&mut context, MIN_TIMELOCK_DELAY as i64).await;
+    process(&mut context, &[
+        instructions::request_uproot(&crop, &farmer, 150),
+        instructions::ripen(&farm.manager, 0),
+    ], &[]).await;
+    process(&mut context, &[
+        instructions::uproot(&crop, &plot, &farmer, &deposit_account, &reward_account, 150, None),
+    ], &[]).await;
+    assert_eq!(token_balance(&mut context, &deposit_account).await, 400);
+    let plot = state::decode_plot(&account_data(&mut context, &plot_key).await).unwrap();
+    assert_eq!(plot.amount, 600);
+    assert_eq!(plot.unbonding_amount, 0);
+    process(&mut context, &[
+        instructions::uproot(&crop, &plot, &farmer, &deposit_account, &reward_account, 600, None),
+    ], &[]).await;
+    assert_eq!(token_balance(&mut context, &deposit_account).await, 1_000);
+}
+
+#[tokio::test]
+async fn checkpoints_stake() {
+    let mut context = start().await;
+    let params = CropParams {
+        deposit_fee: 0,
+        withdraw_fee: 0,
+        referral_fee: 0,
+        end_timestamp: u64::MAX,
+        reward_rate: 0,
+    };
+    let farm = cultivate(&mut context, &params).await;
+    let farmer = context.payer.pubkey();
+    let (crop_key, _) = pda::find_crop(&farm.manager, 0);
+    let (plot_key, _) = pda::find_plot(&farm.manager, &farmer, 0);
+    let crop = state::decode_crop(&account_data(&mut context, &crop_key).await).unwrap();
+    let deposit_account = create_token_account(&mut context, &farm.deposit_mint, &farmer).await;
+    let reward_account = create_token_account(&mut context, &farm.reward_mint, &farmer).await;
+    mint_to(&mut context, &farm.deposit_mint, &deposit_account, 1_000).await;
+
+    let clock: Clock = context.banks_client.get_sysvar().await.unwrap();
+    let sown = clock.unix_timestamp as u64;
+    process(&mut context, &[
+        instructions::till(&farm.manager, 0, &farmer),
+        instructions::sow(&crop, &farmer, &deposit_account, 1_000, None, None),
+    ], &[]).await;
+
+    advance_clock(&mut context, 100).await;
+    let plot = state::decode_plot(&account_data(&mut context, &plot_key).await).unwrap();
+    process(&mut context, &[
+        instructions::uproot(&crop, &plot, &farmer, &deposit_account, &reward_account, 400, None),
+    ], &[]).await;
+
+    let crop = state::decode_crop(&account_data(&mut context, &crop_key).await).unwrap();
+    let plot = state::decode_plot(&account_data(&mut context, &plot_key).await).unwrap();
+    assert_eq!(state::stake_at(&plot, sown - 1), None);
+    assert_eq!(state::stake_at(&plot, sown), Some(1_000));
+    assert_eq!(state::stake_at(&plot, sown + 99), Some(1_000));
+    assert_eq!(state::stake_at(&plot, sown + 100), Some(600));
+    assert_eq!(state::total_stake_at(&crop, sown + 50), Some(1_000));
+    assert_eq!(state::total_stake_at(&crop, u64::MAX), Some(600));
+
+    // Weighing only reads the checkpoints, so the program can be asked about any timestamp
+    process(&mut context, &[instructions::weigh(&farm.manager, 0, &farmer, sown + 50)], &[]).await;
+}
+
+#[tokio::test]
+async fn penalizes_early_withdrawals() {
+    let mut context = start().await;
+    // Never charged, the penalty takes its place
+    let params = CropParams {
+        deposit_fee: 0,
+        withdraw_fee: 50_000_000,
+        referral_fee: 0,
+        end_timestamp: u64::MAX,
+        reward_rate: 0,
+    };
+    let penalty = Penalty { max: 100_000_000, period: 1_000 };
+    let farm = cultivate_with(&mut context, &params, &penalty, 0, 0).await;
+    let farmer = context.payer.pubkey();
+    let (crop_key, _) = pda::find_crop(&farm.manager, 0);
+    let (plot_key, _) = pda::find_plot(&farm.manager, &farmer, 0);
+
+    let deposit_account = create_token_account(&mut context, &farm.deposit_mint, &farmer).await;
+    let reward_account = create_token_account(&mut context, &farm.reward_mint, &farmer).await;
+    mint_to(&mut context, &farm.deposit_mint, &deposit_account, 10_000).await;
+    let crop = state::decode_crop(&account_data(&mut context, &crop_key).await).unwrap();
+    process(&mut context, &[
+        instructions::till(&farm.manager, 0, &farmer),
+        instructions::sow(&crop, &farmer, &deposit_account, 10_000, None, None),
+    ], &[]).await;
+
+    // Halfway through the period half of the penalty is left
+    advance_clock(&mut context, 500).await;
+    let plot = state::decode_plot(&account_data(&mut context, &plot_key).await).unwrap();
+    process(&mut context, &[
+        instructions::uproot(&crop, &plot, &farmer, &deposit_account, &reward_account, 5_000, None),
+    ], &[]).await;
+    assert_eq!(token_balance(&mut context, &deposit_account).await, 4_750);
+
+    // Stretching the period far would charge deposits that had already waited it out
+    let clock: Clock = context.banks_client.get_sysvar().await.unwrap();
+    let effective_timestamp = clock.unix_timestamp as u64 + MIN_TIMELOCK_DELAY;
+    assert!(!try_process(&mut context, &[
+        instructions::recultivate(&farm.manager, 0, &farmer, &params, &Penalty { period: 100_000, ..penalty }, 0, 0, effective_timestamp),
+    ], &[]).await);
+    process(&mut context, &[
+        instructions::recultivate(&farm.manager, 0, &farmer, &params, &Penalty { period: 1_050, ..penalty }, 0, 0, effective_timestamp),
+    ], &[]).await;
+
+    // And none once the deposit is a period old
+    advance_clock(&mut context, 500).await;
+    let plot = state::decode_plot(&account_data(&mut context, &plot_key).await).unwrap();
+    process(&mut context, &[
+        instructions::uproot(&crop, &plot, &farmer, &deposit_account, &reward_account, 5_000, None),
+    ], &[]).await;
+    assert_eq!(token_balance(&mut context, &deposit_account).await, 9_750);
+    let crop = state::decode_crop(&account_data(&mut context, &crop_key).await).unwrap();
+    assert_eq!(crop.fees, 250);
+}
+
+#[tokio::test]
+async fn shares_fees_with_stakers() {
+    let mut context = start().await;
+    let params = CropParams {
+        deposit_fee: 0,
+        withdraw_fee: 100_000_000,
+        referral_fee: 0,
+        end_timestamp: u64::MAX,
+        reward_rate: 0,
+    };
+    let farm = cultivate_with(&mut context, &params, &Penalty::default(), 500_000_000, 0).await;
+    let payer = context.payer.pubkey();
+    let other = Keypair::new();
+    let (crop_key, _) = pda::find_crop(&farm.manager, 0);
+    let crop = state::decode_crop(&account_data(&mut context, &crop_key).await).unwrap();
+
+    let payer_deposits = create_token_account(&mut context, &farm.deposit_mint, &payer).await;
+    let payer_rewards = create_token_account(&mut context, &farm.reward_mint, &payer).await;
+    let other_deposits = create_token_account(&mut context, &farm.deposit_mint, &other.pubkey()).await;
+    let other_rewards = create_token_account(&mut context, &farm.reward_mint, &other.pubkey()).await;
+    mint_to(&mut context, &farm.deposit_mint, &payer_deposits, 1_000).await;
+    mint_to(&mut context, &farm.deposit_mint, &other_deposits, 1_000).await;
+
+    process(&mut context, &[
+        system_instruction::transfer(&payer, &other.pubkey(), 1_000_000_000),
+        instructions::till(&farm.manager, 0, &payer),
+        instructions::till(&farm.manager, 0, &other.pubkey()),
+        instructions::sow(&crop, &payer, &payer_deposits, 1_000, None, None),
+        instructions::sow(&crop, &other.pubkey(), &other_deposits, 1_000, None, None),
+    ], &[&other]).await;
+
+    // Half of the 100 withdraw fee goes to the farmer who stayed
+    let plot = state::decode_plot(&account_data(&mut context, &pda::find_plot(&farm.manager, &other.pubkey(), 0).0).await).unwrap();
+    process(&mut context, &[
+        instructions::uproot(&crop, &plot, &other.pubkey(), &other_deposits, &other_rewards, 1_000, None),
+    ], &[&other]).await;
+    assert_eq!(token_balance(&mut context, &other_deposits).await, 900);
+
+    let crop = state::decode_crop(&account_data(&mut context, &crop_key).await).unwrap();
+    let plot = state::decode_plot(&account_data(&mut context, &pda::find_plot(&farm.manager, &payer, 0).0).await).unwrap();
+    assert_eq!(crop.fees, 50);
+    assert_eq!(crop.staker_fees, 50);
+    assert_eq!(state::pending_fees(&crop, &plot).unwrap(), 50);
+
+    // Harvesting pays it out without touching the stake
+    process(&mut context, &[
+        instructions::harvest_many(&farm.manager, &payer, &[(crop.clone(), plot, payer_rewards, payer_deposits)]),
+    ], &[]).await;
+    assert_eq!(token_balance(&mut context, &payer_deposits).await, 50);
+    let crop = state::decode_crop(&account_data(&mut context, &crop_key).await).unwrap();
+    assert_eq!(crop.staker_fees, 0);
+}
+
+#[tokio::test]
+async fn collects_fees_on_schedule() {
+    let mut context = start().await;
+    let params = CropParams {
+        deposit_fee: 100_000_000,
+        withdraw_fee: 0,
+        referral_fee: 0,
+        end_timestamp: u64::MAX,
+        reward_rate: 0,
+    };
+    let farm = cultivate(&mut context, &params).await;
+    let payer = context.payer.pubkey();
+    let recipient = Pubkey::new_unique();
+    let (crop_key, _) = pda::find_crop(&farm.manager, 0);
+    let crop = state::decode_crop(&account_data(&mut context, &crop_key).await).unwrap();
+
+    let deposit_account = create_token_account(&mut context, &farm.deposit_mint, &payer).await;
+    let recipient_account = create_token_account(&mut context, &farm.deposit_mint, &recipient).await;
+    mint_to(&mut context, &farm.deposit_mint, &deposit_account, 2_000).await;
+    process(&mut context, &[
+        instructions::till(&farm.manager, 0, &payer),
+        instructions::sow(&crop, &payer, &deposit_account, 1_000, None, None),
+    ], &[]).await;
+
+    // Part of the fees, never more than there are, and only to the owner until a recipient is set
+    process(&mut context, &[
+        instructions::collect(&crop, &payer, &deposit_account, Some(40)),
+    ], &[]).await;
+    assert_eq!(token_balance(&mut context, &deposit_account).await, 1_040);
+    assert!(!try_process(&mut context, &[
+        instructions::collect(&crop, &payer, &deposit_account, Some(61)),
+    ], &[]).await);
+    assert!(!try_process(&mut context, &[
+        instructions::collect(&crop, &payer, &recipient_account, None),
+    ], &[]).await);
+
+    // Nobody can reap until the manager sets an interval
+    assert!(!try_process(&mut context, &[
+        instructions::consign(&farm.manager, &payer, &recipient, 0),
+        instructions::reap(&crop, &recipient_account),
+    ], &[]).await);
+    process(&mut context, &[
+        instructions::consign(&farm.manager, &payer, &recipient, 100),
+        instructions::reap(&crop, &recipient_account),
+    ], &[]).await;
+    assert_eq!(token_balance(&mut context, &recipient_account).await, 60);
+    assert!(!try_process(&mut context, &[
+        instructions::collect(&crop, &payer, &deposit_account, None),
+    ], &[]).await);
+
+    // Transactions are told apart by signature, so the early reap rides along with the deposit
+    assert!(!try_process(&mut context, &[
+        instructions::sow(&crop, &payer, &deposit_account, 1_000, None, None),
+        instructions::reap(&crop, &recipient_account),
+    ], &[]).await);
+    process(&mut context, &[
+        instructions::sow(&crop, &payer, &deposit_account, 1_000, None, None),
+    ], &[]).await;
+    advance_clock(&mut context, 100).await;
+    process(&mut context, &[instructions::reap(&crop, &recipient_account)], &[]).await;
+    assert_eq!(token_balance(&mut context, &recipient_account).await, 160);
+}
+
+// Rewrites an account as it was serialized before versioning, keeping its discriminator
+async fn downgrade<T: AnchorSerialize>(context: &mut ProgramTestContext, address: &Pubkey, legacy: &T) {
+    let mut account = context.banks_client.get_account(*address).await.unwrap().unwrap();
+    account.data.truncate(8);
+    legacy.serialize(&mut account.data).unwrap();
+    context.set_account(address, &AccountSharedData::from(account));
+}
+
+#[tokio::test]
+async fn migrates_legacy_accounts() {
+    let mut context = start().await;
+    let params = CropParams {
+        deposit_fee: 0,
+        withdraw_fee: 0,
+        referral_fee: 0,
+        end_timestamp: u64::MAX,
+        reward_rate: 0,
+    };
+    let farm = cultivate(&mut context, &params).await;
+    let farmer = context.payer.pubkey();
+    let (crop_key, _) = pda::find_crop(&farm.manager, 0);
+    let (plot_key, _) = pda::find_plot(&farm.manager, &farmer, 0);
+
+    process(&mut context, &[instructions::till(&farm.manager, 0, &farmer)], &[]).await;
+    let deposit_account = create_token_account(&mut context, &farm.deposit_mint, &farmer).await;
+    let reward_account = create_token_account(&mut context, &farm.reward_mint, &farmer).await;
+    mint_to(&mut context, &farm.deposit_mint, &deposit_account, 1_000).await;
+    let crop = state::decode_crop(&account_data(&mut context, &crop_key).await).unwrap();
+    process(&mut context, &[
+        instructions::sow(&crop, &farmer, &deposit_account, 1_000, None, None),
+    ], &[]).await;
+
+    // Rewards sent straight to the treasury, the way crops were funded before budgets
+    mint_to(&mut context, &farm.reward_mint, &crop.reward_treasury, 5_000).await;
+
+    let manager = state::decode_manager(&account_data(&mut context, &farm.manager).await).unwrap();
+    let plot = state::decode_plot(&account_data(&mut context, &plot_key).await).unwrap();
+    downgrade(&mut context, &farm.manager, &ManagerV0 {
+        owner: manager.owner,
+        crops: manager.crops,
+        rewarder_bump: manager.rewarder_bump,
+    }).await;
+    downgrade(&mut context, &crop_key, &CropV0 {
+        manager: crop.manager,
+        reward_treasury: crop.reward_treasury,
+        reward_mint: crop.reward_mint,
+        deposit_treasury: crop.deposit_treasury,
+        deposit_mint: crop.deposit_mint,
+        end_timestamp: crop.end_timestamp,
+        deposit_fee: crop.deposit_fee,
+        withdraw_fee: crop.withdraw_fee,
+        total_deposited: 1_000,
+        fees: crop.fees,
+        rewards_per_share: crop.rewards_per_share,
+        reward_rate: crop.reward_rate,
+        previous_reward_timestamp: crop.previous_reward_timestamp,
+        paused: false,
+        bump: crop.bump,
+        id: crop.id,
+    }).await;
+    downgrade(&mut context, &plot_key, &PlotV0 {
+        amount: plot.amount,
+        debt: plot.debt,
+        bump: plot.bump,
+    }).await;
+    assert_eq!(account_data(&mut context, &crop_key).await.len(), 8 + CropV0::space());
+    assert!(state::decode_crop(&account_data(&mut context, &crop_key).await).is_err());
+
+    process(&mut context, &[
+        instructions::migrate_manager(&farm.manager, &farmer),
+        instructions::migrate_crop(&farm.manager, 0, &crop.reward_treasury, &farmer),
+        instructions::migrate_plot(&farm.manager, 0, &farmer, &farmer),
+    ], &[]).await;
+
+    let manager = state::decode_manager(&account_data(&mut context, &farm.manager).await).unwrap();
+    assert_eq!(manager.version, state::Manager::VERSION);
+    assert_eq!(manager.owner, farmer);
+    assert_eq!(manager.crops, 1);
+    assert_eq!(manager.timelock_delay, MIN_TIMELOCK_DELAY);
+    assert_eq!(manager.max_fee, MAX_FEE);
+    let crop = state::decode_crop(&account_data(&mut context, &crop_key).await).unwrap();
+    assert_eq!(crop.version, state::Crop::VERSION);
+    assert_eq!(crop.manager, farm.manager);
+    assert_eq!(crop.deposit_mint, farm.deposit_mint);
+    assert_eq!(crop.reward_mint, farm.reward_mint);
+    assert_eq!(crop.end_timestamp, u64::MAX);
+    assert_eq!(crop.total_deposited, 1_000);
+    assert_eq!(crop.id, 0);
+    assert_eq!(crop.reward_budget, 5_000);
+    let plot = state::decode_plot(&account_data(&mut context, &plot_key).await).unwrap();
+    assert_eq!(plot.version, state::Plot::VERSION);
+    assert_eq!(plot.amount, 1_000);
+    assert_eq!(plot.referrer, Pubkey::default());
+
+    // Checkpoints start from the stake at migration
+    let clock: Clock = context.banks_client.get_sysvar().await.unwrap();
+    let now = clock.unix_timestamp as u64;
+    assert_eq!(state::stake_at(&plot, now), Some(1_000));
+    assert_eq!(state::total_stake_at(&crop, now), Some(1_000));
+    assert_eq!(state::stake_at(&plot, now - 1), None);
+
+    // Current accounts are left alone
+    assert!(!try_process(&mut context, &[instructions::migrate_crop(&farm.manager, 0, &crop.reward_treasury, &farmer)], &[]).await);
+
+    process(&mut context, &[
+        instructions::uproot(&crop, &plot, &farmer, &deposit_account, &reward_account, 1_000, None),
+    ], &[]).await;
+    assert_eq!(token_balance(&mut context, &deposit_account).await, 1_000);
+
+    // The widest ceiling a migration leaves can be lowered, but never raised again
+    process(&mut context, &[instructions::hedge(&farm.manager, &farmer, MAX_FEE / 10)], &[]).await;
+    let manager = state::decode_manager(&account_data(&mut context, &farm.manager).await).unwrap();
+    assert_eq!(manager.max_fee, MAX_FEE / 10);
+    assert!(!try_process(&mut context, &[instructions::hedge(&farm.manager, &farmer, MAX_FEE)], &[]).await);
+}
+
+#[tokio::test]
+async fn migrates_version_two_plots() {
+    let mut context = start().await;
+    let params = CropParams {
+        deposit_fee: 0,
+        withdraw_fee: 0,
+        referral_fee: 0,
+        end_timestamp: u64::MAX,
+        reward_rate: 0,
+    };
+    let farm = cultivate(&mut context, &params).await;
+    let farmer = context.payer.pubkey();
+    let keeper = Keypair::new();
+    let (crop_key, _) = pda::find_crop(&farm.manager, 0);
+    let (plot_key, _) = pda::find_plot(&farm.manager, &farmer, 0);
+
+    let deposit_account = create_token_account(&mut context, &farm.deposit_mint, &farmer).await;
+    mint_to(&mut context, &farm.deposit_mint, &deposit_account, 1_000).await;
+    let crop = state::decode_crop(&account_data(&mut context, &crop_key).await).unwrap();
+    process(&mut context, &[
+        instructions::till(&farm.manager, 0, &farmer),
+        instructions::sow(&crop, &farmer, &deposit_account, 1_000, None, None),
+    ], &[]).await;
+
+    // Version 2 plots ended at the checkpoints, before the keeper
+    let plot = state::decode_plot(&account_data(&mut context, &plot_key).await).unwrap();
+    let mut account = context.banks_client.get_account(plot_key).await.unwrap().unwrap();
+    account.data.truncate(8);
+    state::Plot { version: 2, ..plot.clone() }.serialize(&mut account.data).unwrap();
+    account.data.truncate(8 + state::Plot::space() - (32 + 1));
+    context.set_account(&plot_key, &AccountSharedData::from(account));
+    assert!(!try_process(&mut context, &[
+        instructions::hire(&crop, &farmer, &keeper.pubkey(), state::KeeperScope::Compound),
+    ], &[]).await);
+
+    process(&mut context, &[instructions::migrate_plot(&farm.manager, 0, &farmer, &farmer)], &[]).await;
+    let migrated = state::decode_plot(&account_data(&mut context, &plot_key).await).unwrap();
+    assert_eq!(migrated.version, state::Plot::VERSION);
+    assert_eq!(migrated.amount, plot.amount);
+    assert_eq!(migrated.deposit_timestamp, plot.deposit_timestamp);
+    assert_eq!(migrated.checkpoints[0].amount, plot.checkpoints[0].amount);
+    assert_eq!(migrated.checkpoints[0].timestamp, plot.checkpoints[0].timestamp);
+    assert_eq!(migrated.keeper, Pubkey::default());
+
+    process(&mut context, &[
+        instructions::hire(&crop, &farmer, &keeper.pubkey(), state::KeeperScope::Harvest),
+    ], &[]).await;
+    let plot = state::decode_plot(&account_data(&mut context, &plot_key).await).unwrap();
+    assert_eq!(plot.keeper, keeper.pubkey());
+}
@@ -0,0 +1,657 @@
+// Runs arbitrary sequences of farm instructions against an in-process runtime and checks after
+// every step that the treasuries still cover what the farms owe. Rejected instructions are part
+// of the search, a panic or an uncovered treasury is a finding.
+//
+// The runtime loads the compiled program, since the native processor in solana-program-test 1.9
+// cannot grow the accounts every `init` creates through a CPI:
+//
+//     cargo build-bpf --manifest-path programs/farms/Cargo.toml
+//     BPF_OUT_DIR=$PWD/target/deploy cargo fuzz run --fuzz-dir fuzz instruction_sequences
+#![no_main]
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use solana_program_test::{ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    instruction::Instruction,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_instruction,
+    sysvar::clock::Clock,
+    transaction::Transaction,
+    transport::TransportError,
+};
+
+use std::sync::Once;
+
+use farms_client::{instructions, pda, state};
+use farms_client::state::{Crop, CropParams, Penalty, Plot};
+
+const FARMERS: usize = 3;
+const MANAGERS: usize = 2;
+const CROPS: usize = 4;
+const FARMER_BALANCE: u64 = 1_000_000_000_000;
+const MAX_FEE: u64 = 100_000_000;
+const MIN_TIMELOCK_DELAY: u64 = 86_400;
+
+#[derive(Arbitrary, Debug)]
+enum Op {
+    Appoint { timelock_delay: u8 },
+    Cultivate { manager: u8, deposit_fee: u32, withdraw_fee: u32, reward_rate: u16, duration: u16, penalty: (u32, u16), fee_share: u32, cooldown: u8, successor: bool },
+    Recultivate { crop: u8, deposit_fee: u32, withdraw_fee: u32, reward_rate: u16, duration: u16, penalty: (u32, u16), fee_share: u32, cooldown: u8 },
+    Ripen { crop: u8 },
+    Till { farmer: u8, crop: u8 },
+    Sow { farmer: u8, crop: u8, amount: u64 },
+    Uproot { farmer: u8, crop: u8, amount: u64 },
+    RequestUproot { farmer: u8, crop: u8, amount: u64 },
+    CancelUproot { farmer: u8, crop: u8 },
+    HarvestMany { farmer: u8, manager: u8 },
+    Hire { farmer: u8, crop: u8, keeper: u8, compound: bool },
+    Glean { keeper: u8, farmer: u8, crop: u8 },
+    Compound { keeper: u8, farmer: u8, crop: u8 },
+    MigrateStake { farmer: u8, from_crop: u8, to_crop: u8, amount: u64 },
+    Chart { from_crop: u8, to_crop: u8 },
+    Collect { crop: u8, amount: Option<u64> },
+    Tend { crop: u8 },
+    Advance { seconds: u32 },
+}
+
+struct FuzzCrop {
+    manager: Pubkey,
+    id: u64,
+    deposit_mint: Pubkey,
+    reward_mint: Pubkey,
+    // Per farmer
+    deposit_accounts: Vec<Pubkey>,
+    reward_accounts: Vec<Pubkey>,
+    fee_account: Pubkey,
+    funding_account: Pubkey,
+}
+
+struct Harness {
+    context: ProgramTestContext,
+    farmers: Vec<Keypair>,
+    managers: Vec<Pubkey>,
+    crops: Vec<FuzzCrop>,
+}
+
+impl Harness {
+
+    async fn new() -> Harness {
+        assert!(std::env::var("BPF_OUT_DIR").is_ok(), "BPF_OUT_DIR must point at the compiled program");
+        let context = ProgramTest::new("farms", farms::id(), None)
+            .start_with_context()
+            .await;
+        let farmers: Vec<Keypair> = (0..FARMERS).map(|_| Keypair::new()).collect();
+        let payer = context.payer.pubkey();
+        let transfers: Vec<Instruction> = farmers.iter()
+            .map(|farmer| system_instruction::transfer(&payer, &farmer.pubkey(), 10_000_000_000))
+            .collect();
+        let mut harness = Harness { context, farmers, managers: vec![], crops: vec![] };
+        harness.expect(&transfers, &[]).await;
+        harness
+    }
+
+    // Ok(false) when the runtime rejected the transaction, anything else going wrong is a crash
+    async fn process(&mut self, instructions: &[Instruction], signers: &[&Keypair]) -> bool {
+        let transaction = {
+            let mut all_signers = vec![&self.context.payer];
+            all_signers.extend_from_slice(signers);
+            Transaction::new_signed_with_payer(
+                instructions,
+                Some(&self.context.payer.pubkey()),
+                &all_signers,
+                self.context.last_blockhash
+            )
+        };
+        match self.context.banks_client.process_transaction(transaction).await {
+            Ok(()) => true,
+            Err(TransportError::TransactionError(_)) => false,
+            Err(err) => panic!("runtime failure: {}", err),
+        }
+    }
+
+    // For the harness' own setup, which must not fail
+    async fn expect(&mut self, instructions: &[Instruction], signers: &[&Keypair]) {
+        assert!(self.process(instructions, signers).await, "setup transaction failed");
+    }
+
+    async fn now(&mut self) -> u64 {
+        let clock: Clock = self.context.banks_client.get_sysvar().await.unwrap();
+        clock.unix_timestamp as u64
+    }
+
+    async fn advance(&mut self, seconds: u64) {
+        let mut clock: Clock = self.context.banks_client.get_sysvar().await.unwrap();
+        clock.unix_timestamp += seconds as i64;
+        self.context.set_sysvar(&clock);
+    }
+
+    async fn data(&mut self, address: &Pubkey) -> Option<Vec<u8>> {
+        self.context.banks_client.get_account(*address).await.unwrap().map(|account| account.data)
+    }
+
+    async fn crop_state(&mut self, index: usize) -> Crop {
+        let address = pda::find_crop(&self.crops[index].manager, self.crops[index].id).0;
+        state::decode_crop(&self.data(&address).await.unwrap()).unwrap()
+    }
+
+    async fn plot_state(&mut self, index: usize, farmer: usize) -> Option<Plot> {
+        let crop = &self.crops[index];
+        let address = pda::find_plot(&crop.manager, &self.farmers[farmer].pubkey(), crop.id).0;
+        self.data(&address).await.map(|data| state::decode_plot(&data).unwrap())
+    }
+
+    async fn balance(&mut self, address: &Pubkey) -> u64 {
+        let data = self.data(address).await.unwrap();
+        spl_token::state::Account::unpack(&data).unwrap().amount
+    }
+
+    async fn create_mint(&mut self) -> Pubkey {
+        let mint = Keypair::new();
+        let rent = self.context.banks_client.get_rent().await.unwrap();
+        let payer = self.context.payer.pubkey();
+        self.expect(&[
+            system_instruction::create_account(
+                &payer,
+                &mint.pubkey(),
+                rent.minimum_balance(spl_token::state::Mint::LEN),
+                spl_token::state::Mint::LEN as u64,
+                &spl_token::id()
+            ),
+            spl_token::instruction::initialize_mint(&spl_token::id(), &mint.pubkey(), &payer, None, 9).unwrap(),
+        ], &[&mint]).await;
+        mint.pubkey()
+    }
+
+    async fn create_token_account(&mut self, mint: &Pubkey, owner: &Pubkey) -> Pubkey {
+        let account = Keypair::new();
+        let rent = self.context.banks_client.get_rent().await.unwrap();
+        let payer = self.context.payer.pubkey();
+        self.expect(&[
+            system_instruction::create_account(
+                &payer,
+                &account.pubkey(),
+                rent.minimum_balance(spl_token::state::Account::LEN),
+                spl_token::state::Account::LEN as u64,
+                &spl_token::id()
+            ),
+            spl_token::instruction::initialize_account(&spl_token::id(), &account.pubkey(), mint, owner).unwrap(),
+        ], &[&account]).await;
+        account.pubkey()
+    }
+
+    async fn mint_to(&mut self, mint: &Pubkey, to: &Pubkey, amount: u64) {
+        if amount == 0 {
+            return;
+        }
+        let payer = self.context.payer.pubkey();
+        self.expect(&[
+            spl_token::instruction::mint_to(&spl_token::id(), mint, to, &payer, &[], amount).unwrap(),
+        ], &[]).await;
+    }
+
+    // Budgets only grow through fertilize, so every schedule is funded that way
+    async fn fertilize(&mut self, index: usize, amount: u64) {
+        if amount == 0 {
+            return;
+        }
+        let crop = self.crop_state(index).await;
+        let (reward_mint, funding_account) = (self.crops[index].reward_mint, self.crops[index].funding_account);
+        self.mint_to(&reward_mint, &funding_account, amount).await;
+        let payer = self.context.payer.pubkey();
+        self.expect(&[instructions::fertilize(&crop, &payer, &funding_account, amount)], &[]).await;
+    }
+
+    async fn run(&mut self, op: Op) {
+        let payer = self.context.payer.pubkey();
+        match op {
+
+            Op::Appoint { timelock_delay } => {
+                if self.managers.len() == MANAGERS {
+                    return;
+                }
+                let manager = Keypair::new();
+                if self.process(&[
+                    instructions::appoint(&manager.pubkey(), &payer, MIN_TIMELOCK_DELAY + timelock_delay as u64, MAX_FEE),
+                ], &[&manager]).await {
+                    self.managers.push(manager.pubkey());
+                }
+            }
+
+            Op::Cultivate { manager, deposit_fee, withdraw_fee, reward_rate, duration, penalty, fee_share, cooldown, successor } => {
+                if self.managers.is_empty() || self.crops.len() == CROPS {
+                    return;
+                }
+                let manager = self.managers[manager as usize % self.managers.len()];
+                let crop_id = state::decode_manager(&self.data(&manager).await.unwrap()).unwrap().crops;
+                // Successors take the same deposit mint as one of the manager's crops so stake can
+                // migrate between them
+                let predecessor = self.crops.iter().find(|crop| crop.manager == manager).map(|crop| crop.deposit_mint);
+                let deposit_mint = match predecessor {
+                    Some(deposit_mint) if successor => deposit_mint,
+                    _ => self.create_mint().await,
+                };
+                let reward_mint = self.create_mint().await;
+                let deposit_treasury = Keypair::new();
+                let reward_treasury = Keypair::new();
+                let params = CropParams {
+                    deposit_fee: deposit_fee as u64,
+                    withdraw_fee: withdraw_fee as u64,
+                    referral_fee: 0,
+                    end_timestamp: self.now().await + duration as u64,
+                    reward_rate: reward_rate as u64,
+                };
+                if !self.process(&[
+                    instructions::cultivate(
+                        &manager,
+                        crop_id,
+                        &payer,
+                        &deposit_mint,
+                        &reward_mint,
+                        &deposit_treasury.pubkey(),
+                        &reward_treasury.pubkey(),
+                        true,
+                        &params,
+                        &Penalty { max: penalty.0 as u64, period: penalty.1 as u64 },
+                        fee_share as u64,
+                        cooldown as u64,
+                        None
+                    ),
+                ], &[&deposit_treasury, &reward_treasury]).await {
+                    return;
+                }
+
+                let mut deposit_accounts = vec![];
+                let mut reward_accounts = vec![];
+                for farmer in 0..FARMERS {
+                    let owner = self.farmers[farmer].pubkey();
+                    let deposit_account = self.create_token_account(&deposit_mint, &owner).await;
+                    self.mint_to(&deposit_mint, &deposit_account, FARMER_BALANCE).await;
+                    deposit_accounts.push(deposit_account);
+                    reward_accounts.push(self.create_token_account(&reward_mint, &owner).await);
+                }
+                let fee_account = self.create_token_account(&deposit_mint, &payer).await;
+                let funding_account = self.create_token_account(&reward_mint, &payer).await;
+                self.crops.push(FuzzCrop {
+                    manager,
+                    id: crop_id,
+                    deposit_mint,
+                    reward_mint,
+                    deposit_accounts,
+                    reward_accounts,
+                    fee_account,
+                    funding_account,
+                });
+
+                // Fund the whole schedule up front, the way a careful operator would
+                self.fertilize(self.crops.len() - 1, reward_rate as u64 * duration as u64).await;
+            }
+
+            Op::Recultivate { crop, deposit_fee, withdraw_fee, reward_rate, duration, penalty, fee_share, cooldown } => {
+                if self.crops.is_empty() {
+                    return;
+                }
+                let index = crop as usize % self.crops.len();
+                let now = self.now().await;
+                let params = CropParams {
+                    deposit_fee: deposit_fee as u64,
+                    withdraw_fee: withdraw_fee as u64,
+                    referral_fee: 0,
+                    end_timestamp: now + duration as u64,
+                    reward_rate: reward_rate as u64,
+                };
+                let manager = self.crops[index].manager;
+                let delay = state::decode_manager(&self.data(&manager).await.unwrap()).unwrap().timelock_delay;
+                self.process(&[
+                    instructions::recultivate(
+                        &manager,
+                        self.crops[index].id,
+                        &payer,
+                        &params,
+                        &Penalty { max: penalty.0 as u64, period: penalty.1 as u64 },
+                        fee_share as u64,
+                        cooldown as u64,
+                        now + delay
+                    ),
+                ], &[]).await;
+            }
+
+            Op::Ripen { crop } => {
+                if self.crops.is_empty() {
+                    return;
+                }
+                let index = crop as usize % self.crops.len();
+                let (manager, crop_id) = (self.crops[index].manager, self.crops[index].id);
+                if self.process(&[instructions::ripen(&manager, crop_id)], &[]).await {
+                    // Fund the new schedule from now on
+                    let crop = self.crop_state(index).await;
+                    let now = self.now().await;
+                    let funding = crop.reward_rate * crop.end_timestamp.saturating_sub(now);
+                    self.fertilize(index, funding).await;
+                }
+            }
+
+            Op::Till { farmer, crop } => {
+                if self.crops.is_empty() {
+                    return;
+                }
+                let index = crop as usize % self.crops.len();
+                let farmer = &self.farmers[farmer as usize % FARMERS];
+                let instruction = instructions::till(&self.crops[index].manager, self.crops[index].id, &farmer.pubkey());
+                let farmer = Keypair::from_bytes(&farmer.to_bytes()).unwrap();
+                self.process(&[instruction], &[&farmer]).await;
+            }
+
+            Op::Sow { farmer, crop, amount } => {
+                if self.crops.is_empty() {
+                    return;
+                }
+                let index = crop as usize % self.crops.len();
+                let farmer = farmer as usize % FARMERS;
+                let crop = self.crop_state(index).await;
+                let keypair = Keypair::from_bytes(&self.farmers[farmer].to_bytes()).unwrap();
+                let instruction = instructions::sow(
+                    &crop,
+                    &keypair.pubkey(),
+                    &self.crops[index].deposit_accounts[farmer],
+                    amount,
+                    None,
+                    None
+                );
+                self.process(&[instruction], &[&keypair]).await;
+            }
+
+            Op::Uproot { farmer, crop, amount } => {
+                if self.crops.is_empty() {
+                    return;
+                }
+                let index = crop as usize % self.crops.len();
+                let farmer = farmer as usize % FARMERS;
+                let crop = self.crop_state(index).await;
+                let plot = match self.plot_state(index, farmer).await {
+                    Some(plot) => plot,
+                    None => return,
+                };
+                // Mostly stay within the plot so withdrawals get exercised, but keep some overdraws
+                let amount = amount % (plot.amount + plot.unbonding_amount + 2);
+                let keypair = Keypair::from_bytes(&self.farmers[farmer].to_bytes()).unwrap();
+                let instruction = instructions::uproot(
+                    &crop,
+                    &plot,
+                    &keypair.pubkey(),
+                    &self.crops[index].deposit_accounts[farmer],
+                    &self.crops[index].reward_accounts[farmer],
+                    amount,
+                    None
+                );
+                self.process(&[instruction], &[&keypair]).await;
+            }
+
+            Op::RequestUproot { farmer, crop, amount } => {
+                if self.crops.is_empty() {
+                    return;
+                }
+                let index = crop as usize % self.crops.len();
+                let farmer = farmer as usize % FARMERS;
+                let crop = self.crop_state(index).await;
+                let plot = match self.plot_state(index, farmer).await {
+                    Some(plot) => plot,
+                    None => return,
+                };
+                let amount = amount % (plot.amount + 2);
+                let keypair = Keypair::from_bytes(&self.farmers[farmer].to_bytes()).unwrap();
+                let instruction = instructions::request_uproot(&crop, &keypair.pubkey(), amount);
+                self.process(&[instruction], &[&keypair]).await;
+            }
+
+            Op::CancelUproot { farmer, crop } => {
+                if self.crops.is_empty() {
+                    return;
+                }
+                let index = crop as usize % self.crops.len();
+                let farmer = farmer as usize % FARMERS;
+                let crop = self.crop_state(index).await;
+                let keypair = Keypair::from_bytes(&self.farmers[farmer].to_bytes()).unwrap();
+                self.process(&[instructions::cancel_uproot(&crop, &keypair.pubkey())], &[&keypair]).await;
+            }
+
+            Op::HarvestMany { farmer, manager } => {
+                if self.managers.is_empty() {
+                    return;
+                }
+                let farmer = farmer as usize % FARMERS;
+                let manager = self.managers[manager as usize % self.managers.len()];
+                // Every crop the farmer has a plot in under that manager
+                let mut harvests = Vec::new();
+                for index in 0..self.crops.len() {
+                    if self.crops[index].manager != manager {
+                        continue;
+                    }
+                    if let Some(plot) = self.plot_state(index, farmer).await {
+                        let crop = self.crop_state(index).await;
+                        harvests.push((
+                            crop,
+                            plot,
+                            self.crops[index].reward_accounts[farmer],
+                            self.crops[index].deposit_accounts[farmer]
+                        ));
+                    }
+                }
+                let keypair = Keypair::from_bytes(&self.farmers[farmer].to_bytes()).unwrap();
+                let instruction = instructions::harvest_many(&manager, &keypair.pubkey(), &harvests);
+                self.process(&[instruction], &[&keypair]).await;
+            }
+
+            Op::Hire { farmer, crop, keeper, compound } => {
+                if self.crops.is_empty() {
+                    return;
+                }
+                let index = crop as usize % self.crops.len();
+                let farmer = farmer as usize % FARMERS;
+                let crop = self.crop_state(index).await;
+                let keeper = self.farmers[keeper as usize % FARMERS].pubkey();
+                let scope = if compound { state::KeeperScope::Compound } else { state::KeeperScope::Harvest };
+                let keypair = Keypair::from_bytes(&self.farmers[farmer].to_bytes()).unwrap();
+                let instruction = instructions::hire(&crop, &keypair.pubkey(), &keeper, scope);
+                self.process(&[instruction], &[&keypair]).await;
+            }
+
+            // Keepers may be the farmer, their keeper or any other farmer, who should be turned away
+            Op::Glean { keeper, farmer, crop } => {
+                if self.crops.is_empty() {
+                    return;
+                }
+                let index = crop as usize % self.crops.len();
+                let farmer = farmer as usize % FARMERS;
+                let plot = match self.plot_state(index, farmer).await {
+                    Some(plot) => plot,
+                    None => return,
+                };
+                let crop = self.crop_state(index).await;
+                let keeper = Keypair::from_bytes(&self.farmers[keeper as usize % FARMERS].to_bytes()).unwrap();
+                let instruction = instructions::glean(
+                    &crop,
+                    &plot,
+                    &self.farmers[farmer].pubkey(),
+                    &keeper.pubkey(),
+                    &self.crops[index].reward_accounts[farmer],
+                    &self.crops[index].deposit_accounts[farmer]
+                );
+                self.process(&[instruction], &[&keeper]).await;
+            }
+
+            Op::Compound { keeper, farmer, crop } => {
+                if self.crops.is_empty() {
+                    return;
+                }
+                let index = crop as usize % self.crops.len();
+                let farmer = farmer as usize % FARMERS;
+                let plot = match self.plot_state(index, farmer).await {
+                    Some(plot) => plot,
+                    None => return,
+                };
+                let crop = self.crop_state(index).await;
+                let keeper = Keypair::from_bytes(&self.farmers[keeper as usize % FARMERS].to_bytes()).unwrap();
+                let instruction = instructions::compound(&crop, &plot, &self.farmers[farmer].pubkey(), &keeper.pubkey());
+                self.process(&[instruction], &[&keeper]).await;
+            }
+
+            Op::MigrateStake { farmer, from_crop, to_crop, amount } => {
+                if self.crops.is_empty() {
+                    return;
+                }
+                let farmer = farmer as usize % FARMERS;
+                let from_index = from_crop as usize % self.crops.len();
+                let to_index = to_crop as usize % self.crops.len();
+                let plot = match self.plot_state(from_index, farmer).await {
+                    Some(plot) => plot,
+                    None => return,
+                };
+                let amount = amount % (plot.amount + 2);
+                let from_crop = self.crop_state(from_index).await;
+                let to_crop = self.crop_state(to_index).await;
+                let keypair = Keypair::from_bytes(&self.farmers[farmer].to_bytes()).unwrap();
+                let instruction = instructions::migrate_stake(&from_crop, &to_crop, &keypair.pubkey(), amount);
+                self.process(&[instruction], &[&keypair]).await;
+            }
+
+            Op::Chart { from_crop, to_crop } => {
+                if self.crops.is_empty() {
+                    return;
+                }
+                let from = &self.crops[from_crop as usize % self.crops.len()];
+                let to = &self.crops[to_crop as usize % self.crops.len()];
+                let instruction = instructions::chart(&from.manager, from.id, to.id, &payer);
+                self.process(&[instruction], &[]).await;
+            }
+
+            Op::Collect { crop, amount } => {
+                if self.crops.is_empty() {
+                    return;
+                }
+                let index = crop as usize % self.crops.len();
+                let crop = self.crop_state(index).await;
+                let fee_account = self.crops[index].fee_account;
+                // Partial collections, with the odd one asking for more than there is
+                let amount = amount.map(|amount| amount % (crop.fees + 2));
+                self.process(&[instructions::collect(&crop, &payer, &fee_account, amount)], &[]).await;
+            }
+
+            Op::Tend { crop } => {
+                if self.crops.is_empty() {
+                    return;
+                }
+                let crop = self.crop_state(crop as usize % self.crops.len()).await;
+                self.process(&[instructions::tend(&crop)], &[]).await;
+            }
+
+            Op::Advance { seconds } => {
+                self.advance(seconds as u64).await;
+            }
+
+        }
+    }
+
+    async fn check_invariants(&mut self) {
+        let now = self.now().await;
+        for index in 0..self.crops.len() {
+            let crop = self.crop_state(index).await;
+
+            let mut staked = 0u128;
+            let mut unbonding = 0u128;
+            let mut owed = 0u128;
+            let mut fees_owed = 0u128;
+            for farmer in 0..FARMERS {
+                if let Some(plot) = self.plot_state(index, farmer).await {
+                    assert_eq!(
+                        state::stake_at(&plot, now).unwrap_or(0), plot.amount,
+                        "crop {} farmer {} latest checkpoint is behind", index, farmer
+                    );
+                    staked += plot.amount as u128;
+                    unbonding += plot.unbonding_amount as u128;
+                    owed += plot.unclaimed as u128;
+                    owed += state::pending_rewards(&crop, &plot, now).unwrap() as u128;
+                    fees_owed += state::pending_fees(&crop, &plot).unwrap() as u128;
+                }
+            }
+            assert_eq!(staked, crop.total_deposited as u128, "crop {} plots do not add up", index);
+            assert_eq!(
+                state::total_stake_at(&crop, now).unwrap_or(0), crop.total_deposited,
+                "crop {} latest checkpoint is behind", index
+            );
+
+            // Stake cooling down has left the crop but not the treasury
+            let deposits = self.balance(&crop.deposit_treasury).await;
+            assert!(
+                deposits as u128 >= staked + unbonding + crop.fees as u128 + crop.staker_fees as u128,
+                "crop {} deposit treasury holds {} but owes {} deposited, {} unbonding, {} fees and {} to stakers",
+                index, deposits, staked, unbonding, crop.fees, crop.staker_fees
+            );
+            assert!(
+                fees_owed <= crop.staker_fees as u128,
+                "crop {} stakers are owed {} fees but only {} are held",
+                index, fees_owed, crop.staker_fees
+            );
+
+            let rewards = self.balance(&crop.reward_treasury).await;
+            assert!(
+                rewards as u128 >= owed,
+                "crop {} reward treasury holds {} but owes {}",
+                index, rewards, owed
+            );
+            assert!(
+                crop.rewards_paid <= crop.reward_budget,
+                "crop {} paid {} rewards out of a budget of {}",
+                index, crop.rewards_paid, crop.reward_budget
+            );
+        }
+    }
+
+}
+
+// A fixed sequence that has to get as far as sowing and uprooting, so a runtime that rejects
+// everything cannot pass for a fuzzer that finds nothing
+async fn smoke_test() {
+    let mut harness = Harness::new().await;
+    harness.run(Op::Appoint { timelock_delay: 0 }).await;
+    harness.run(Op::Cultivate {
+        manager: 0,
+        deposit_fee: 0,
+        withdraw_fee: 0,
+        reward_rate: 1,
+        duration: 1_000,
+        penalty: (0, 0),
+        fee_share: 0,
+        cooldown: 0,
+        successor: false,
+    }).await;
+    assert_eq!(harness.crops.len(), 1, "smoke run could not cultivate a crop");
+    harness.run(Op::Till { farmer: 0, crop: 0 }).await;
+    harness.run(Op::Sow { farmer: 0, crop: 0, amount: 1_000 }).await;
+    let plot = harness.plot_state(0, 0).await.expect("smoke run could not till a plot");
+    assert_eq!(plot.amount, 1_000, "smoke run could not sow");
+    harness.run(Op::Advance { seconds: 10 }).await;
+    harness.run(Op::Uproot { farmer: 0, crop: 0, amount: 1_000 }).await;
+    let plot = harness.plot_state(0, 0).await.unwrap();
+    assert_eq!(plot.amount, 0, "smoke run could not uproot");
+    harness.check_invariants().await;
+}
+
+static SMOKE_TEST: Once = Once::new();
+
+fuzz_target!(|ops: Vec<Op>| {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    SMOKE_TEST.call_once(|| runtime.block_on(smoke_test()));
+    runtime.block_on(async {
+        let mut harness = Harness::new().await;
+        for op in ops.into_iter().take(64) {
+            harness.run(op).await;
+            harness.check_invariants().await;
+        }
+    });
+});
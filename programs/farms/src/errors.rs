@@ -1,5 +1,6 @@
 use anchor_lang::prelude::*;
 use anchor_lang::error;
+use farms_accounting::AccountingError;
 
 #[error]
 pub enum FarmError {
@@ -16,10 +17,127 @@ pub enum FarmError {
     #[msg("Numerical overflow error")]
     NumericalOverflowError,
 
+    #[msg("The crop is paused")]
+    CropIsPaused,
+
     #[msg("Fee is too large")]
     InvalidFee,
 
+    #[msg("Fees cannot increase this much while farmers are staked")]
+    FeeIncreaseTooLarge,
+
     #[msg("Amount is too large")]
     AmountIsTooLarge,
 
-}
\ No newline at end of file
+    #[msg("Updates must take effect after the timelock delay")]
+    TimelockTooShort,
+
+    #[msg("The crop has no pending update")]
+    NoPendingUpdate,
+
+    #[msg("The pending update is not effective yet")]
+    UpdateNotEffective,
+
+    #[msg("The crop still has deposits")]
+    CropNotEmpty,
+
+    #[msg("The crop does not issue receipts")]
+    ReceiptsNotEnabled,
+
+    #[msg("Receipt account does not belong to the crop")]
+    InvalidReceiptAccount,
+
+    #[msg("Farmers cannot refer themselves")]
+    InvalidReferrer,
+
+    #[msg("Referral account does not belong to the referrer")]
+    InvalidReferralAccount,
+
+    #[msg("Account is not of the expected type")]
+    InvalidAccountType,
+
+    #[msg("Account is already on the current version")]
+    AccountIsCurrent,
+
+    #[msg("Metadata is too long")]
+    MetadataTooLong,
+
+    #[msg("Reward rates are set by allocation points")]
+    RateIsApportioned,
+
+    #[msg("Every crop of the manager must be passed in order")]
+    MissingCrops,
+
+    #[msg("The crop's reward budget is too small")]
+    InsufficientBudget,
+
+    #[msg("Fees can only be collected to the fee recipient")]
+    InvalidFeeDestination,
+
+    #[msg("Fees are not due for collection yet")]
+    CollectionNotDue,
+
+    #[msg("Accounts do not belong to the farmer's plot in one of the manager's crops")]
+    InvalidHarvestAccounts,
+
+    #[msg("Stake in crops that issue receipts cannot be migrated")]
+    CropIssuesReceipts,
+
+    #[msg("Route does not belong to these crops")]
+    InvalidRoute,
+
+    #[msg("Crops with a cooldown cannot issue receipts")]
+    CooldownWithReceipts,
+
+    #[msg("The crop has no cooldown")]
+    NoCooldown,
+
+    #[msg("Requested stake is still cooling down")]
+    StillUnbonding,
+
+    #[msg("Stake in crops with a cooldown has to be requested first")]
+    CropHasCooldown,
+
+    #[msg("Signer is neither the farmer nor a keeper allowed to do this")]
+    NotKeeper,
+
+    #[msg("Rewards are not paid in the deposit mint")]
+    CannotCompound,
+
+    #[msg("More is staked than there are receipts in circulation")]
+    UnbackedReceipts,
+
+    #[msg("Timelock delay is shorter than the minimum")]
+    TimelockDelayTooShort,
+
+    #[msg("Metadata account is not the crop's metadata address")]
+    InvalidMetadataAccount,
+
+    #[msg("Apportion calls continuing an apportionment must repeat its rate and timestamp")]
+    ApportionmentMismatch,
+
+    #[msg("Only the farmer can reckon their stake up")]
+    OnlyFarmerCanReckonUp,
+
+    #[msg("Reward treasury does not belong to the crop")]
+    InvalidRewardTreasury,
+
+    #[msg("The queued apportionment is being rotated in")]
+    ApportionmentRotating,
+
+    #[msg("The crop has a pending update")]
+    UpdatePending,
+
+    #[msg("The fee ceiling can only be lowered")]
+    FeeCeilingRaised,
+
+}
+
+impl From<AccountingError> for FarmError {
+    fn from(error: AccountingError) -> Self {
+        match error {
+            AccountingError::Overflow => FarmError::NumericalOverflowError,
+            AccountingError::InsufficientStake => FarmError::AmountIsTooLarge,
+        }
+    }
+}
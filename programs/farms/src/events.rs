@@ -0,0 +1,50 @@
+use anchor_lang::prelude::*;
+
+#[event]
+pub struct PendingRewards {
+
+    pub crop: Pubkey,
+
+    pub plot: Pubkey,
+
+    // Projected to the timestamp below, the crop on chain may lag behind
+    pub rewards_per_share: u64,
+
+    // What the farmer would receive if they harvested now, after any referral kickback and within
+    // the crop's reward budget
+    pub rewards: u64,
+
+    // Shared fees the farmer would receive in the deposit mint
+    pub fees: u64,
+
+    pub timestamp: u64,
+
+}
+
+#[event]
+pub struct StakeAt {
+
+    pub crop: Pubkey,
+
+    pub plot: Pubkey,
+
+    pub timestamp: u64,
+
+    // None when the timestamp is older than the checkpoints kept
+    pub amount: Option<u64>,
+
+    pub total_deposited: Option<u64>,
+
+}
+
+#[event]
+pub struct Tended {
+
+    pub crop: Pubkey,
+
+    pub rewards_per_share: u64,
+
+    // Stops at the crop's end timestamp, after which nothing more accrues
+    pub previous_reward_timestamp: u64,
+
+}
@@ -1,6 +1,7 @@
 use anchor_lang::prelude::*;
 
 use crate::state::Manager;
+use crate::instructions::utils::{self, MAX_FEE};
 
 #[derive(Accounts)]
 #[instruction(rewarder_bump: u8)]
@@ -27,10 +28,24 @@ pub struct Appoint<'info> {
 
 }
 
-pub fn handler(ctx: Context<Appoint>, rewarder_bump: u8) -> ProgramResult {
+pub fn handler(
+    ctx: Context<Appoint>,
+    rewarder_bump: u8,
+    timelock_delay: u64,
+    max_fee: u64
+) -> ProgramResult {
+    utils::assert_valid_fee(max_fee, MAX_FEE)?;
+    utils::assert_valid_timelock_delay(timelock_delay)?;
+
     let manager = &mut ctx.accounts.manager;
     manager.owner = ctx.accounts.signer.key();
+    manager.operator = Pubkey::default();
+    manager.fee_collector = Pubkey::default();
+    manager.pauser = Pubkey::default();
     manager.crops = 0;
+    manager.timelock_delay = timelock_delay;
+    manager.max_fee = max_fee;
     manager.rewarder_bump = rewarder_bump;
+    manager.version = Manager::VERSION;
     Ok(())
 }
@@ -0,0 +1,76 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::FarmError;
+use crate::state::{Manager, Crop};
+use crate::instructions::utils;
+
+// Queues allocation points for a run of crops, so a manager with many crops can apportion over
+// several transactions. The crops follow as remaining accounts in id order with one entry of the
+// allocation points per crop, starting from crop 0 to begin a new apportionment or from the first
+// crop without points to continue one. Each crop ripens into its new rate once every crop has
+// points and the timelock has passed, through rotate.
+#[derive(Accounts)]
+pub struct Apportion<'info> {
+
+    #[account(mut, constraint = manager.is_operator(&operator.key()))]
+    pub manager: Account<'info, Manager>,
+
+    pub operator: Signer<'info>,
+
+}
+
+pub fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, Apportion<'info>>,
+    emission_rate: u64,
+    alloc_points: Vec<u64>,
+    effective_timestamp: u64
+) -> ProgramResult {
+    if ctx.remaining_accounts.is_empty() || alloc_points.len() != ctx.remaining_accounts.len() {
+        return Err(FarmError::MissingCrops.into());
+    }
+
+    let manager = &mut ctx.accounts.manager;
+    if manager.rotated_crops > 0 {
+        return Err(FarmError::ApportionmentRotating.into());
+    }
+    utils::assert_valid_delay(
+        utils::get_current_timestamp()?,
+        effective_timestamp,
+        manager.timelock_delay
+    )?;
+
+    for (index, (info, points)) in ctx.remaining_accounts.iter().zip(alloc_points).enumerate() {
+        let mut crop = Account::<Crop>::try_from(info)?;
+        if crop.manager != manager.key() {
+            return Err(FarmError::MissingCrops.into());
+        }
+
+        // Starting over drops whatever an earlier apportionment had queued so far
+        if index == 0 && crop.id == 0 {
+            manager.pending_emission_rate = emission_rate;
+            manager.pending_total_alloc_points = 0;
+            manager.apportioned_crops = 0;
+            manager.pending_apportion_timestamp = effective_timestamp;
+        } else if index == 0 && (
+            emission_rate != manager.pending_emission_rate
+            || effective_timestamp != manager.pending_apportion_timestamp
+        ) {
+            return Err(FarmError::ApportionmentMismatch.into());
+        }
+        if crop.id != manager.apportioned_crops {
+            return Err(FarmError::MissingCrops.into());
+        }
+
+        manager.pending_total_alloc_points = match manager.pending_total_alloc_points.checked_add(points) {
+            Some(x) => x,
+            None => return Err(FarmError::NumericalOverflowError.into()),
+        };
+        manager.apportioned_crops += 1;
+
+        crop.pending_alloc_points = points;
+        crop.pending_apportion = true;
+        crop.exit(ctx.program_id)?;
+    }
+
+    Ok(())
+}
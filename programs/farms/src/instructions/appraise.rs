@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{Crop, Plot};
+use crate::events::PendingRewards;
+use crate::instructions::utils;
+
+// Read only, meant to be simulated by clients which then read the emitted event
+#[derive(Accounts)]
+pub struct Appraise<'info> {
+
+    #[account(
+        seeds = [b"crop".as_ref(), crop.manager.as_ref(), &crop.id.to_le_bytes()],
+        bump = crop.bump
+    )]
+    pub crop: Account<'info, Crop>,
+
+    #[account(
+        seeds = [b"plot".as_ref(), crop.manager.as_ref(), farmer.key().as_ref(), &crop.id.to_le_bytes()],
+        bump = plot.bump
+    )]
+    pub plot: Account<'info, Plot>,
+
+    pub farmer: AccountInfo<'info>,
+
+}
+
+pub fn handler(ctx: Context<Appraise>) -> ProgramResult {
+    let current_timestamp = utils::get_current_timestamp()?;
+
+    // Work on copies so nothing is written back
+    let mut crop: Crop = (*ctx.accounts.crop).clone();
+    let mut plot: Plot = (*ctx.accounts.plot).clone();
+    utils::update_crop_at(&mut crop, current_timestamp)?;
+    utils::settle_plot(&crop, &mut plot)?;
+
+    let payable = utils::payable_rewards(&crop, plot.unclaimed);
+    let (rewards, _) = utils::split_referral(&crop, &plot, payable)?;
+
+    emit!(PendingRewards {
+        crop: ctx.accounts.crop.key(),
+        plot: ctx.accounts.plot.key(),
+        rewards_per_share: crop.rewards_per_share,
+        rewards,
+        fees: plot.unclaimed_fees,
+        timestamp: current_timestamp,
+    });
+
+    Ok(())
+}
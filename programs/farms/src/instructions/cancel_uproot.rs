@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{Crop, Plot};
+use crate::instructions::utils;
+
+#[derive(Accounts)]
+pub struct CancelUproot<'info> {
+
+    #[account(
+        mut,
+        seeds = [b"crop".as_ref(), crop.manager.as_ref(), &crop.id.to_le_bytes()],
+        bump = crop.bump
+    )]
+    pub crop: Account<'info, Crop>,
+
+    #[account(
+        mut,
+        seeds = [b"plot".as_ref(), crop.manager.as_ref(), farmer.key().as_ref(), &crop.id.to_le_bytes()],
+        bump = plot.bump
+    )]
+    pub plot: Account<'info, Plot>,
+
+    pub farmer: Signer<'info>,
+
+}
+
+pub fn handler(ctx: Context<CancelUproot>) -> ProgramResult {
+    utils::assert_not_paused(&ctx.accounts.crop)?;
+    utils::assert_non_zero(ctx.accounts.plot.unbonding_amount)?;
+
+    utils::cancel_withdrawal(
+        &mut ctx.accounts.crop,
+        &mut ctx.accounts.plot,
+        utils::get_current_timestamp()?
+    )
+}
@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, Mint};
+
+use crate::state::{Manager, Crop};
+use crate::instructions::utils;
+
+#[derive(Accounts)]
+#[instruction(seed: u8)]
+pub struct Certify<'info> {
+
+    #[account(constraint = manager.is_operator(&operator.key()))]
+    pub manager: Account<'info, Manager>,
+
+    #[account(
+        mut,
+        seeds = [b"crop".as_ref(), crop.manager.as_ref(), &crop.id.to_le_bytes()],
+        bump = crop.bump,
+        has_one = manager,
+        has_one = deposit_mint
+    )]
+    pub crop: Account<'info, Crop>,
+
+    #[account(
+        init,
+        payer = operator,
+        seeds = [b"receipt".as_ref(), crop.key().as_ref()],
+        bump = seed,
+        mint::decimals = deposit_mint.decimals,
+        mint::authority = crop
+    )]
+    pub receipt_mint: Account<'info, Mint>,
+
+    pub deposit_mint: Account<'info, Mint>,
+
+    pub operator: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub system_program: Program<'info, System>,
+
+    pub rent: Sysvar<'info, Rent>,
+
+}
+
+pub fn handler(ctx: Context<Certify>) -> ProgramResult {
+
+    // Existing deposits would have no receipts to burn on withdrawal
+    utils::assert_crop_is_empty(&ctx.accounts.crop)?;
+
+    // Stake cooling down has left the crop and would have no receipts to burn either
+    utils::assert_cooldown_compatible(ctx.accounts.crop.cooldown, &ctx.accounts.receipt_mint.key())?;
+    utils::assert_cooldown_compatible(ctx.accounts.crop.pending_cooldown, &ctx.accounts.receipt_mint.key())?;
+
+    ctx.accounts.crop.receipt_mint = ctx.accounts.receipt_mint.key();
+
+    Ok(())
+}
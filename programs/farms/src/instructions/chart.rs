@@ -0,0 +1,52 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{Manager, Crop, Route};
+
+// Lets farmers move stake from one crop to another without paying either crop's fees
+#[derive(Accounts)]
+#[instruction(seed: u8)]
+pub struct Chart<'info> {
+
+    #[account(has_one = owner)]
+    pub manager: Account<'info, Manager>,
+
+    #[account(
+        seeds = [b"crop".as_ref(), manager.key().as_ref(), &from_crop.id.to_le_bytes()],
+        bump = from_crop.bump,
+        has_one = manager,
+    )]
+    pub from_crop: Box<Account<'info, Crop>>,
+
+    #[account(
+        seeds = [b"crop".as_ref(), manager.key().as_ref(), &to_crop.id.to_le_bytes()],
+        bump = to_crop.bump,
+        has_one = manager,
+        constraint = to_crop.key() != from_crop.key(),
+        constraint = to_crop.deposit_mint == from_crop.deposit_mint
+    )]
+    pub to_crop: Box<Account<'info, Crop>>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Route::space(),
+        seeds = [b"route".as_ref(), from_crop.key().as_ref(), to_crop.key().as_ref()],
+        bump = seed
+    )]
+    pub route: Account<'info, Route>,
+
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    pub rent: Sysvar<'info, Rent>,
+
+}
+
+pub fn handler(ctx: Context<Chart>, seed: u8) -> ProgramResult {
+    let route = &mut ctx.accounts.route;
+    route.from_crop = ctx.accounts.from_crop.key();
+    route.to_crop = ctx.accounts.to_crop.key();
+    route.bump = seed;
+    Ok(())
+}
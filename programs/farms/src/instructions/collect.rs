@@ -1,18 +1,24 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Token, TokenAccount, Transfer};
 
+use crate::errors::FarmError;
 use crate::state::{Manager, Crop};
+use crate::instructions::utils;
 
+// Collects from a single crop. Collecting across many crops is batched client side, one collect
+// per crop in the same transaction as collect_many in the client builds them, so each transfer is
+// still checked against the fee destination on its own.
 #[derive(Accounts)]
 pub struct Collect<'info> {
 
-    #[account(mut, has_one = owner)]
+    #[account(constraint = manager.is_fee_collector(&collector.key()))]
     pub manager: Account<'info, Manager>,
 
     #[account(
         mut,
         seeds = [b"crop".as_ref(), crop.manager.as_ref(), &crop.id.to_le_bytes()],
         bump = crop.bump,
+        has_one = manager,
         has_one = deposit_treasury
     )]
     pub crop: Account<'info, Crop>,
@@ -26,7 +32,7 @@ pub struct Collect<'info> {
     )]
     pub deposit_token_account: Account<'info, TokenAccount>,
 
-    pub owner: Signer<'info>,
+    pub collector: Signer<'info>,
 
     pub token_program: Program<'info, Token>,
 
@@ -52,10 +58,15 @@ impl<'info> Collect<'info> {
 
 }
 
-pub fn handler(ctx: Context<Collect>) -> ProgramResult {
+// Collects everything when no amount is given
+pub fn handler(ctx: Context<Collect>, amount: Option<u64>) -> ProgramResult {
+    utils::assert_fee_destination(&ctx.accounts.manager, &ctx.accounts.deposit_token_account)?;
 
-    let fees = ctx.accounts.crop.fees;
-    ctx.accounts.crop.fees = 0;
+    let fees = amount.unwrap_or(ctx.accounts.crop.fees);
+    if fees > ctx.accounts.crop.fees {
+        return Err(FarmError::AmountIsTooLarge.into());
+    }
+    ctx.accounts.crop.fees -= fees;
     ctx.accounts.transfer_from_treasury(fees)?;
 
     Ok(())
@@ -0,0 +1,120 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::account_info::next_account_info;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+
+use crate::state::{Manager, Crop, Plot, KeeperScope};
+use crate::instructions::utils;
+
+// Harvests for the farmer and stakes the rewards back into the crop, signed by them or a keeper
+// allowed to compound. Only for crops paying rewards in the deposit mint. The referral account
+// follows as a remaining account when the plot has a referrer.
+#[derive(Accounts)]
+pub struct Compound<'info> {
+
+    pub manager: Account<'info, Manager>,
+
+    #[account(
+        seeds = [b"rewarder".as_ref(), crop.manager.as_ref()],
+        bump = manager.rewarder_bump
+    )]
+    pub rewarder_pda: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"crop".as_ref(), crop.manager.as_ref(), &crop.id.to_le_bytes()],
+        bump = crop.bump,
+        has_one = manager,
+        has_one = reward_treasury,
+        has_one = deposit_treasury
+    )]
+    pub crop: Box<Account<'info, Crop>>,
+
+    #[account(
+        mut,
+        seeds = [b"plot".as_ref(), crop.manager.as_ref(), farmer.key().as_ref(), &crop.id.to_le_bytes()],
+        bump = plot.bump
+    )]
+    pub plot: Account<'info, Plot>,
+
+    #[account(mut)]
+    pub reward_treasury: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub deposit_treasury: Account<'info, TokenAccount>,
+
+    pub farmer: AccountInfo<'info>,
+
+    pub keeper: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+
+}
+
+impl<'info> Compound<'info> {
+
+    fn transfer_rewards(&self, amount: u64) -> ProgramResult {
+        token::transfer(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                Transfer {
+                    from: self.reward_treasury.to_account_info(),
+                    to: self.deposit_treasury.to_account_info(),
+                    authority: self.rewarder_pda.to_account_info()
+                },
+                &[&[b"rewarder".as_ref(), self.crop.manager.as_ref(), &[self.manager.rewarder_bump]]]
+            ),
+            amount
+        )?;
+        Ok(())
+    }
+
+}
+
+pub fn handler(ctx: Context<Compound>) -> ProgramResult {
+    utils::assert_keeper(
+        &ctx.accounts.plot,
+        &ctx.accounts.farmer.key(),
+        &ctx.accounts.keeper.key(),
+        KeeperScope::Compound
+    )?;
+    utils::assert_compoundable(&ctx.accounts.crop)?;
+    utils::assert_not_paused(&ctx.accounts.crop)?;
+    // Receipts would have to be minted to the farmer, which a keeper cannot be trusted to pick
+    utils::assert_no_receipts(&ctx.accounts.crop)?;
+
+    let current_timestamp = utils::get_current_timestamp()?;
+    let rewards = utils::harvest(&mut ctx.accounts.crop, &mut ctx.accounts.plot, current_timestamp)?;
+    let rewards = utils::draw_budget(&mut ctx.accounts.crop, &mut ctx.accounts.plot, rewards)?;
+    let (rewards, kickback) = utils::split_referral(&ctx.accounts.crop, &ctx.accounts.plot, rewards)?;
+    if ctx.accounts.plot.referrer != Pubkey::default() {
+        let mut referral = utils::load_referral(
+            next_account_info(&mut ctx.remaining_accounts.iter())?,
+            &ctx.accounts.crop.key(),
+            &ctx.accounts.plot.referrer
+        )?;
+        utils::credit_referral(&mut referral, kickback)?;
+        referral.exit(ctx.program_id)?;
+    }
+
+    // Restaked without a deposit fee, and as of the plot's deposit timestamp so compounding never
+    // restarts the withdraw penalty. An empty plot has no deposit to date back to, so its
+    // rewards count as deposited now.
+    if rewards > 0 {
+        ctx.accounts.transfer_rewards(rewards)?;
+        let deposit_timestamp = if ctx.accounts.plot.amount == 0 {
+            current_timestamp
+        } else {
+            ctx.accounts.plot.deposit_timestamp
+        };
+        utils::deposit_at(
+            &mut ctx.accounts.crop,
+            &mut ctx.accounts.plot,
+            rewards,
+            0,
+            deposit_timestamp,
+            current_timestamp
+        )?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,22 @@
+use anchor_lang::prelude::*;
+
+use crate::state::Manager;
+
+#[derive(Accounts)]
+pub struct Consign<'info> {
+
+    #[account(
+        mut,
+        has_one = owner
+    )]
+    pub manager: ProgramAccount<'info, Manager>,
+
+    pub owner: Signer<'info>,
+
+}
+
+pub fn handler(ctx: Context<Consign>, fee_recipient: Pubkey, collect_interval: u64) -> ProgramResult {
+    ctx.accounts.manager.fee_recipient = fee_recipient;
+    ctx.accounts.manager.collect_interval = collect_interval;
+    Ok(())
+}
@@ -1,14 +1,16 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::account_info::next_account_info;
 use anchor_spl::token::{Token, TokenAccount, Mint};
 
-use crate::state::{Manager, Crop};
+use crate::errors::FarmError;
+use crate::state::{Manager, Crop, CropParams, Penalty, Metadata, MetadataParams};
 use crate::instructions::utils;
 
 #[derive(Accounts)]
 #[instruction(seed: u8)]
 pub struct Cultivate<'info> {
 
-    #[account(mut, has_one = owner)]
+    #[account(mut, constraint = manager.is_operator(&operator.key()))]
     pub manager: Account<'info, Manager>,
 
     #[account(
@@ -19,7 +21,7 @@ pub struct Cultivate<'info> {
 
     #[account(
         init,
-        payer = owner,
+        payer = operator,
         space = 8 + Crop::space(),
         seeds = [b"crop".as_ref(), manager.key().as_ref(), &manager.crops.to_le_bytes()],
         bump = seed
@@ -28,7 +30,7 @@ pub struct Cultivate<'info> {
 
     #[account(
         init, 
-        payer = owner, 
+        payer = operator, 
         token::mint = deposit_mint,
         token::authority = crop
     )]
@@ -36,7 +38,7 @@ pub struct Cultivate<'info> {
 
     #[account(
         init_if_needed,
-        payer = owner,
+        payer = operator,
         token::mint = reward_mint,
         token::authority = rewarder_pda
     )]
@@ -48,7 +50,7 @@ pub struct Cultivate<'info> {
     #[account(mut)]
     pub reward_mint: Account<'info, Mint>,
 
-    pub owner: Signer<'info>,
+    pub operator: Signer<'info>,
 
     pub token_program: Program<'info, Token>,
 
@@ -58,17 +60,30 @@ pub struct Cultivate<'info> {
 
 }
 
-pub fn handler(
-    ctx: Context<Cultivate>, 
+#[allow(clippy::too_many_arguments)]
+pub fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, Cultivate<'info>>,
     deposit_fee: u64,
     withdraw_fee: u64,
+    referral_fee: u64,
     end_timestamp: u64,
     reward_rate: u64,
+    penalty: Penalty,
+    fee_share: u64,
+    cooldown: u64,
+    metadata: Option<MetadataParams>,
     seed: u8
 ) -> ProgramResult {
 
-    utils::assert_valid_fee(deposit_fee)?;
-    utils::assert_valid_fee(withdraw_fee)?;
+    let max_fee = ctx.accounts.manager.max_fee;
+    utils::assert_valid_fee(deposit_fee, max_fee)?;
+    utils::assert_valid_fee(withdraw_fee, max_fee)?;
+    utils::assert_valid_fee(referral_fee, max_fee)?;
+    utils::assert_valid_fee(penalty.max, max_fee)?;
+    utils::assert_valid_fee(fee_share, utils::MAX_FEE)?;
+
+    // Crops of an apportioned manager start without points, apportion gives them a rate
+    utils::assert_not_apportioned(&ctx.accounts.manager, reward_rate)?;
 
     // Construct the new crop
     let crop = &mut ctx.accounts.crop;
@@ -77,19 +92,72 @@ pub fn handler(
     crop.reward_mint = ctx.accounts.reward_mint.key();
     crop.deposit_treasury = ctx.accounts.deposit_treasury.key();
     crop.deposit_mint = ctx.accounts.deposit_mint.key();
+    crop.receipt_mint = Pubkey::default();
     crop.end_timestamp = end_timestamp;
     crop.deposit_fee = deposit_fee;
     crop.withdraw_fee = withdraw_fee;
+    crop.referral_fee = referral_fee;
     crop.reward_rate = reward_rate;
     crop.total_deposited = 0u64;
     crop.rewards_per_share = 0u64;
     crop.previous_reward_timestamp = utils::get_current_timestamp()?;
+    crop.penalty = penalty;
+    crop.fee_share = fee_share;
+    crop.cooldown = cooldown;
+    crop.fees_per_share = 0u64;
+    crop.staker_fees = 0u64;
+    crop.pending_params = CropParams::default();
+    crop.pending_penalty = Penalty::default();
+    crop.pending_fee_share = 0u64;
+    crop.pending_cooldown = 0u64;
+    crop.pending_timestamp = 0u64;
+    crop.reward_budget = 0u64;
+    crop.rewards_paid = 0u64;
     crop.paused = false;
     crop.bump = seed;
     crop.id = ctx.accounts.manager.crops;
-
-    // Increment the crop count
-    ctx.accounts.manager.crops += 1;
+    crop.version = Crop::VERSION;
+
+    // Increment the crop count. A fully queued apportionment stays complete, the new crop simply
+    // has no points in it.
+    let manager = &mut ctx.accounts.manager;
+    if manager.apportioned_crops == manager.crops {
+        manager.apportioned_crops += 1;
+    }
+    manager.crops += 1;
+
+    // Metadata given up front is written right away so the crop never shows up nameless. Its
+    // account is the only remaining account.
+    if let Some(metadata) = metadata {
+        utils::assert_valid_metadata(&metadata.name, &metadata.uri, &metadata.tags)?;
+        let crop_key = ctx.accounts.crop.key();
+        let info = next_account_info(&mut ctx.remaining_accounts.iter())?;
+        let (address, bump) = Pubkey::find_program_address(
+            &[b"metadata".as_ref(), crop_key.as_ref()],
+            ctx.program_id
+        );
+        if *info.key != address {
+            return Err(FarmError::InvalidMetadataAccount.into());
+        }
+        utils::create_pda_account(
+            info,
+            &ctx.accounts.operator.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            &ctx.accounts.rent,
+            8 + Metadata::space(),
+            &[b"metadata".as_ref(), crop_key.as_ref(), &[bump]],
+            ctx.program_id
+        )?;
+        let account = Metadata {
+            crop: crop_key,
+            name: metadata.name,
+            uri: metadata.uri,
+            tags: metadata.tags,
+            bump,
+        };
+        let mut data = info.try_borrow_mut_data()?;
+        account.try_serialize(&mut &mut data[..])?;
+    }
 
     Ok(())
 }
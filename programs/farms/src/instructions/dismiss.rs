@@ -0,0 +1,30 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{Crop, Plot, KeeperScope};
+
+#[derive(Accounts)]
+pub struct Dismiss<'info> {
+
+    #[account(
+        seeds = [b"crop".as_ref(), crop.manager.as_ref(), &crop.id.to_le_bytes()],
+        bump = crop.bump
+    )]
+    pub crop: Box<Account<'info, Crop>>,
+
+    #[account(
+        mut,
+        seeds = [b"plot".as_ref(), crop.manager.as_ref(), farmer.key().as_ref(), &crop.id.to_le_bytes()],
+        bump = plot.bump
+    )]
+    pub plot: Account<'info, Plot>,
+
+    pub farmer: Signer<'info>,
+
+}
+
+pub fn handler(ctx: Context<Dismiss>) -> ProgramResult {
+    let plot = &mut ctx.accounts.plot;
+    plot.keeper = Pubkey::default();
+    plot.keeper_scope = KeeperScope::Harvest;
+    Ok(())
+}
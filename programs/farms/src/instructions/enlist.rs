@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{Crop, Referral};
+
+#[derive(Accounts)]
+#[instruction(seed: u8)]
+pub struct Enlist<'info> {
+
+    #[account(
+        seeds = [b"crop".as_ref(), crop.manager.as_ref(), &crop.id.to_le_bytes()],
+        bump = crop.bump
+    )]
+    pub crop: Account<'info, Crop>,
+
+    #[account(
+        init,
+        payer = referrer,
+        space = 8 + Referral::space(),
+        seeds = [b"referral".as_ref(), crop.key().as_ref(), referrer.key().as_ref()],
+        bump = seed
+    )]
+    pub referral: Account<'info, Referral>,
+
+    pub referrer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    pub rent: Sysvar<'info, Rent>,
+
+}
+
+pub fn handler(ctx: Context<Enlist>, seed: u8) -> ProgramResult {
+    let referral = &mut ctx.accounts.referral;
+    referral.crop = ctx.accounts.crop.key();
+    referral.referrer = ctx.accounts.referrer.key();
+    referral.referred = 0;
+    referral.unclaimed = 0;
+    referral.total_earned = 0;
+    referral.bump = seed;
+    Ok(())
+}
@@ -0,0 +1,27 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{Manager, Crop};
+
+#[derive(Accounts)]
+pub struct Fallow<'info> {
+
+    #[account(constraint = manager.is_pauser(&pauser.key()))]
+    pub manager: Account<'info, Manager>,
+
+    #[account(
+        mut,
+        seeds = [b"crop".as_ref(), crop.manager.as_ref(), &crop.id.to_le_bytes()],
+        bump = crop.bump,
+        has_one = manager
+    )]
+    pub crop: Account<'info, Crop>,
+
+    pub pauser: Signer<'info>,
+
+}
+
+// Paused crops stop taking deposits, withdrawals are always allowed
+pub fn handler(ctx: Context<Fallow>, paused: bool) -> ProgramResult {
+    ctx.accounts.crop.paused = paused;
+    Ok(())
+}
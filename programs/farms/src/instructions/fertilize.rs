@@ -0,0 +1,67 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+
+use crate::errors::FarmError;
+use crate::state::Crop;
+use crate::instructions::utils;
+
+// Anyone can fund a crop, adding to its budget
+#[derive(Accounts)]
+pub struct Fertilize<'info> {
+
+    #[account(
+        mut,
+        seeds = [b"crop".as_ref(), crop.manager.as_ref(), &crop.id.to_le_bytes()],
+        bump = crop.bump,
+        has_one = reward_treasury
+    )]
+    pub crop: Account<'info, Crop>,
+
+    #[account(mut)]
+    pub reward_treasury: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = crop.reward_mint == from_token_account.mint.key()
+    )]
+    pub from_token_account: Account<'info, TokenAccount>,
+
+    pub funder: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+
+}
+
+impl<'info> Fertilize<'info> {
+
+    fn transfer_to_treasury(&self, amount: u64) -> ProgramResult {
+        token::transfer(
+            CpiContext::new(
+                self.token_program.to_account_info(),
+                Transfer {
+                    from: self.from_token_account.to_account_info(),
+                    to: self.reward_treasury.to_account_info(),
+                    authority: self.funder.to_account_info()
+                }
+            ),
+            amount
+        )?;
+        Ok(())
+    }
+
+}
+
+pub fn handler(ctx: Context<Fertilize>, amount: u64) -> ProgramResult {
+    utils::assert_non_zero(amount)?;
+    utils::assert_sufficient_funds(&ctx.accounts.from_token_account, amount)?;
+
+    ctx.accounts.transfer_to_treasury(amount)?;
+
+    let crop = &mut ctx.accounts.crop;
+    crop.reward_budget = match crop.reward_budget.checked_add(amount) {
+        Some(x) => x,
+        None => return Err(FarmError::NumericalOverflowError.into()),
+    };
+
+    Ok(())
+}
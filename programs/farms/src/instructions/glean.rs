@@ -0,0 +1,134 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::account_info::next_account_info;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+
+use crate::state::{Manager, Crop, Plot, KeeperScope};
+use crate::instructions::utils;
+
+// Harvests for the farmer, signed by them or their keeper, along with their share of fees. The
+// referral account follows as a remaining account when the plot has a referrer.
+#[derive(Accounts)]
+pub struct Glean<'info> {
+
+    pub manager: Account<'info, Manager>,
+
+    #[account(
+        seeds = [b"rewarder".as_ref(), crop.manager.as_ref()],
+        bump = manager.rewarder_bump
+    )]
+    pub rewarder_pda: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"crop".as_ref(), crop.manager.as_ref(), &crop.id.to_le_bytes()],
+        bump = crop.bump,
+        has_one = manager,
+        has_one = reward_treasury,
+        has_one = deposit_treasury
+    )]
+    pub crop: Box<Account<'info, Crop>>,
+
+    #[account(
+        mut,
+        seeds = [b"plot".as_ref(), crop.manager.as_ref(), farmer.key().as_ref(), &crop.id.to_le_bytes()],
+        bump = plot.bump
+    )]
+    pub plot: Account<'info, Plot>,
+
+    #[account(mut)]
+    pub reward_treasury: Account<'info, TokenAccount>,
+
+    // Keepers cannot send the rewards anywhere but the farmer's own account
+    #[account(
+        mut,
+        constraint = crop.reward_mint == reward_token_account.mint.key(),
+        constraint = reward_token_account.owner == farmer.key()
+    )]
+    pub reward_token_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub deposit_treasury: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = crop.deposit_mint == deposit_token_account.mint.key(),
+        constraint = deposit_token_account.owner == farmer.key()
+    )]
+    pub deposit_token_account: Account<'info, TokenAccount>,
+
+    pub farmer: AccountInfo<'info>,
+
+    pub keeper: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+
+}
+
+impl<'info> Glean<'info> {
+
+    fn transfer_rewards(&self, amount: u64) -> ProgramResult {
+        token::transfer(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                Transfer {
+                    from: self.reward_treasury.to_account_info(),
+                    to: self.reward_token_account.to_account_info(),
+                    authority: self.rewarder_pda.to_account_info()
+                },
+                &[&[b"rewarder".as_ref(), self.crop.manager.as_ref(), &[self.manager.rewarder_bump]]]
+            ),
+            amount
+        )?;
+        Ok(())
+    }
+
+    fn transfer_fees(&self, amount: u64) -> ProgramResult {
+        token::transfer(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                Transfer {
+                    from: self.deposit_treasury.to_account_info(),
+                    to: self.deposit_token_account.to_account_info(),
+                    authority: self.crop.to_account_info()
+                },
+                &[&[b"crop".as_ref(), self.crop.manager.as_ref(), &self.crop.id.to_le_bytes(), &[self.crop.bump]]]
+            ),
+            amount
+        )?;
+        Ok(())
+    }
+
+}
+
+pub fn handler(ctx: Context<Glean>) -> ProgramResult {
+    utils::assert_keeper(
+        &ctx.accounts.plot,
+        &ctx.accounts.farmer.key(),
+        &ctx.accounts.keeper.key(),
+        KeeperScope::Harvest
+    )?;
+
+    let current_timestamp = utils::get_current_timestamp()?;
+    let rewards = utils::harvest(&mut ctx.accounts.crop, &mut ctx.accounts.plot, current_timestamp)?;
+    let rewards = utils::draw_budget(&mut ctx.accounts.crop, &mut ctx.accounts.plot, rewards)?;
+    let (rewards, kickback) = utils::split_referral(&ctx.accounts.crop, &ctx.accounts.plot, rewards)?;
+    if ctx.accounts.plot.referrer != Pubkey::default() {
+        let mut referral = utils::load_referral(
+            next_account_info(&mut ctx.remaining_accounts.iter())?,
+            &ctx.accounts.crop.key(),
+            &ctx.accounts.plot.referrer
+        )?;
+        utils::credit_referral(&mut referral, kickback)?;
+        referral.exit(ctx.program_id)?;
+    }
+    if rewards > 0 {
+        ctx.accounts.transfer_rewards(rewards)?;
+    }
+
+    let fees = utils::claim_fees(&mut ctx.accounts.crop, &mut ctx.accounts.plot)?;
+    if fees > 0 {
+        ctx.accounts.transfer_fees(fees)?;
+    }
+
+    Ok(())
+}
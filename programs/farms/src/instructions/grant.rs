@@ -0,0 +1,26 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{Manager, Role};
+
+#[derive(Accounts)]
+pub struct Grant<'info> {
+
+    #[account(
+        mut,
+        has_one = owner
+    )]
+    pub manager: Account<'info, Manager>,
+
+    pub owner: Signer<'info>,
+
+}
+
+pub fn handler(ctx: Context<Grant>, role: Role, grantee: Pubkey) -> ProgramResult {
+    let manager = &mut ctx.accounts.manager;
+    match role {
+        Role::Operator => manager.operator = grantee,
+        Role::FeeCollector => manager.fee_collector = grantee,
+        Role::Pauser => manager.pauser = grantee,
+    }
+    Ok(())
+}
@@ -0,0 +1,126 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::account_info::next_account_info;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+
+use crate::errors::FarmError;
+use crate::state::{Manager, Crop, Plot};
+use crate::instructions::utils;
+
+// Each crop to harvest follows as remaining accounts: the crop, the farmer's plot in it, the
+// crop's reward treasury, the farmer's reward token account, the crop's deposit treasury and the
+// farmer's deposit token account, then the referral account when the plot has a referrer. The
+// farmer's share of fees is paid out of the deposit treasury along with the rewards.
+#[derive(Accounts)]
+pub struct HarvestMany<'info> {
+
+    pub manager: Account<'info, Manager>,
+
+    #[account(
+        seeds = [b"rewarder".as_ref(), manager.key().as_ref()],
+        bump = manager.rewarder_bump
+    )]
+    pub rewarder_pda: AccountInfo<'info>,
+
+    pub farmer: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+
+}
+
+impl<'info> HarvestMany<'info> {
+
+    fn transfer_rewards(
+        &self,
+        reward_treasury: &AccountInfo<'info>,
+        reward_token_account: &AccountInfo<'info>,
+        amount: u64
+    ) -> ProgramResult {
+        token::transfer(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                Transfer {
+                    from: reward_treasury.clone(),
+                    to: reward_token_account.clone(),
+                    authority: self.rewarder_pda.to_account_info()
+                },
+                &[&[b"rewarder".as_ref(), self.manager.key().as_ref(), &[self.manager.rewarder_bump]]]
+            ),
+            amount
+        )?;
+        Ok(())
+    }
+
+    fn transfer_fees(
+        &self,
+        crop: &Account<'info, Crop>,
+        deposit_treasury: &AccountInfo<'info>,
+        deposit_token_account: &AccountInfo<'info>,
+        amount: u64
+    ) -> ProgramResult {
+        token::transfer(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                Transfer {
+                    from: deposit_treasury.clone(),
+                    to: deposit_token_account.clone(),
+                    authority: crop.to_account_info()
+                },
+                &[&[b"crop".as_ref(), crop.manager.as_ref(), &crop.id.to_le_bytes(), &[crop.bump]]]
+            ),
+            amount
+        )?;
+        Ok(())
+    }
+
+}
+
+pub fn handler<'info>(ctx: Context<'_, '_, '_, 'info, HarvestMany<'info>>) -> ProgramResult {
+    let current_timestamp = utils::get_current_timestamp()?;
+    let remaining = &mut ctx.remaining_accounts.iter();
+
+    while remaining.len() > 0 {
+        let crop_info = next_account_info(remaining)?;
+        let plot_info = next_account_info(remaining)?;
+        let reward_treasury = next_account_info(remaining)?;
+        let reward_token_account = next_account_info(remaining)?;
+        let deposit_treasury = next_account_info(remaining)?;
+        let deposit_token_account = next_account_info(remaining)?;
+
+        let mut crop = Account::<Crop>::try_from(crop_info)?;
+        let mut plot = Account::<Plot>::try_from(plot_info)?;
+        if crop.manager != ctx.accounts.manager.key()
+            || *reward_treasury.key != crop.reward_treasury
+            || *deposit_treasury.key != crop.deposit_treasury
+        {
+            return Err(FarmError::InvalidHarvestAccounts.into());
+        }
+        utils::assert_plot_address(plot_info.key, &plot, &crop, &ctx.accounts.farmer.key(), ctx.program_id)?;
+        if Account::<TokenAccount>::try_from(reward_token_account)?.mint != crop.reward_mint
+            || Account::<TokenAccount>::try_from(deposit_token_account)?.mint != crop.deposit_mint
+        {
+            return Err(FarmError::InvalidHarvestAccounts.into());
+        }
+
+        let rewards = utils::harvest(&mut crop, &mut plot, current_timestamp)?;
+        let rewards = utils::draw_budget(&mut crop, &mut plot, rewards)?;
+        let (rewards, kickback) = utils::split_referral(&crop, &plot, rewards)?;
+        if plot.referrer != Pubkey::default() {
+            let mut referral = utils::load_referral(next_account_info(remaining)?, &crop.key(), &plot.referrer)?;
+            utils::credit_referral(&mut referral, kickback)?;
+            referral.exit(ctx.program_id)?;
+        }
+        let fees = utils::claim_fees(&mut crop, &mut plot)?;
+
+        // Written back before the next set so a crop passed twice pays nothing the second time
+        crop.exit(ctx.program_id)?;
+        plot.exit(ctx.program_id)?;
+        if rewards > 0 {
+            ctx.accounts.transfer_rewards(reward_treasury, reward_token_account, rewards)?;
+        }
+        if fees > 0 {
+            ctx.accounts.transfer_fees(&crop, deposit_treasury, deposit_token_account, fees)?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,28 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::FarmError;
+use crate::state::Manager;
+
+// Lowers the fee ceiling, it can never be raised again. Fees already charged by crops stay until
+// they are next updated.
+#[derive(Accounts)]
+pub struct Hedge<'info> {
+
+    #[account(
+        mut,
+        has_one = owner
+    )]
+    pub manager: Account<'info, Manager>,
+
+    pub owner: Signer<'info>,
+
+}
+
+pub fn handler(ctx: Context<Hedge>, max_fee: u64) -> ProgramResult {
+    let manager = &mut ctx.accounts.manager;
+    if max_fee > manager.max_fee {
+        return Err(FarmError::FeeCeilingRaised.into());
+    }
+    manager.max_fee = max_fee;
+    Ok(())
+}
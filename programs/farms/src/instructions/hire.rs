@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{Crop, Plot, KeeperScope};
+
+#[derive(Accounts)]
+pub struct Hire<'info> {
+
+    #[account(
+        seeds = [b"crop".as_ref(), crop.manager.as_ref(), &crop.id.to_le_bytes()],
+        bump = crop.bump
+    )]
+    pub crop: Box<Account<'info, Crop>>,
+
+    #[account(
+        mut,
+        seeds = [b"plot".as_ref(), crop.manager.as_ref(), farmer.key().as_ref(), &crop.id.to_le_bytes()],
+        bump = plot.bump
+    )]
+    pub plot: Account<'info, Plot>,
+
+    pub farmer: Signer<'info>,
+
+}
+
+// Replaces any keeper the plot already has
+pub fn handler(ctx: Context<Hire>, keeper: Pubkey, scope: KeeperScope) -> ProgramResult {
+    let plot = &mut ctx.accounts.plot;
+    plot.keeper = keeper;
+    plot.keeper_scope = scope;
+    Ok(())
+}
@@ -0,0 +1,83 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+
+use crate::errors::FarmError;
+use crate::state::{Manager, Crop};
+use crate::instructions::utils;
+
+// Funds a new reward period and derives the rate and end timestamp from it. Whatever the running
+// period has yet to emit rolls into the new one. A queued update would overwrite the new schedule
+// when it ripens, so it has to be pruned or ripened first.
+#[derive(Accounts)]
+pub struct Irrigate<'info> {
+
+    #[account(constraint = manager.is_operator(&operator.key()))]
+    pub manager: Account<'info, Manager>,
+
+    #[account(
+        mut,
+        seeds = [b"crop".as_ref(), manager.key().as_ref(), &crop.id.to_le_bytes()],
+        bump = crop.bump,
+        has_one = manager,
+        has_one = reward_treasury
+    )]
+    pub crop: Account<'info, Crop>,
+
+    #[account(mut)]
+    pub reward_treasury: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = crop.reward_mint == from_token_account.mint.key()
+    )]
+    pub from_token_account: Account<'info, TokenAccount>,
+
+    pub operator: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+
+}
+
+impl<'info> Irrigate<'info> {
+
+    fn transfer_to_treasury(&self, amount: u64) -> ProgramResult {
+        token::transfer(
+            CpiContext::new(
+                self.token_program.to_account_info(),
+                Transfer {
+                    from: self.from_token_account.to_account_info(),
+                    to: self.reward_treasury.to_account_info(),
+                    authority: self.operator.to_account_info()
+                }
+            ),
+            amount
+        )?;
+        Ok(())
+    }
+
+}
+
+pub fn handler(ctx: Context<Irrigate>, amount: u64, duration: u64) -> ProgramResult {
+    utils::assert_non_zero(amount)?;
+    utils::assert_non_zero(duration)?;
+    utils::assert_sufficient_funds(&ctx.accounts.from_token_account, amount)?;
+    if ctx.accounts.manager.is_apportioned() {
+        return Err(FarmError::RateIsApportioned.into());
+    }
+    if ctx.accounts.crop.pending_timestamp > 0 {
+        return Err(FarmError::UpdatePending.into());
+    }
+
+    ctx.accounts.transfer_to_treasury(amount)?;
+
+    let crop = &mut ctx.accounts.crop;
+    utils::extend_rewards(crop, amount, duration, utils::get_current_timestamp()?)?;
+
+    // The crop can pay out what was just added
+    crop.reward_budget = match crop.reward_budget.checked_add(amount) {
+        Some(x) => x,
+        None => return Err(FarmError::NumericalOverflowError.into()),
+    };
+
+    Ok(())
+}
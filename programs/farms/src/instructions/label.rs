@@ -0,0 +1,54 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{Manager, Crop, Metadata};
+use crate::instructions::utils;
+
+// Creates the crop's metadata on first use, later calls overwrite it
+#[derive(Accounts)]
+#[instruction(seed: u8)]
+pub struct Label<'info> {
+
+    #[account(constraint = manager.is_operator(&operator.key()))]
+    pub manager: Account<'info, Manager>,
+
+    #[account(
+        seeds = [b"crop".as_ref(), manager.key().as_ref(), &crop.id.to_le_bytes()],
+        bump = crop.bump,
+        has_one = manager,
+    )]
+    pub crop: Account<'info, Crop>,
+
+    #[account(
+        init_if_needed,
+        payer = operator,
+        space = 8 + Metadata::space(),
+        seeds = [b"metadata".as_ref(), crop.key().as_ref()],
+        bump = seed
+    )]
+    pub metadata: Account<'info, Metadata>,
+
+    pub operator: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    pub rent: Sysvar<'info, Rent>,
+
+}
+
+pub fn handler(
+    ctx: Context<Label>,
+    seed: u8,
+    name: String,
+    uri: String,
+    tags: Vec<String>
+) -> ProgramResult {
+    utils::assert_valid_metadata(&name, &uri, &tags)?;
+
+    let metadata = &mut ctx.accounts.metadata;
+    metadata.crop = ctx.accounts.crop.key();
+    metadata.name = name;
+    metadata.uri = uri;
+    metadata.tags = tags;
+    metadata.bump = seed;
+    Ok(())
+}
@@ -0,0 +1,84 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+
+use crate::errors::FarmError;
+use crate::state::{Crop, CropV0};
+use crate::instructions::utils;
+
+// Unchecked because crops on an older layout fail to deserialize until they are grown
+#[derive(Accounts)]
+pub struct MigrateCrop<'info> {
+
+    #[account(mut)]
+    pub crop: AccountInfo<'info>,
+
+    // Checked against the crop once it is decoded
+    pub reward_treasury: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    pub rent: Sysvar<'info, Rent>,
+
+}
+
+pub fn handler(ctx: Context<MigrateCrop>) -> ProgramResult {
+    let info = &ctx.accounts.crop;
+    utils::assert_account_type::<Crop>(info, ctx.program_id)?;
+    let legacy = utils::read_version_zero::<CropV0>(info, CropV0::space())?;
+    utils::grow_account(
+        info,
+        &ctx.accounts.payer.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        &ctx.accounts.rent,
+        8 + Crop::space()
+    )?;
+    if legacy.is_some() {
+        utils::clear_account(info)?;
+    }
+
+    let mut crop = Account::<Crop>::try_from(info)?;
+    utils::assert_outdated(crop.version, Crop::VERSION)?;
+
+    // Everything version 0 crops did not have starts out empty
+    if let Some(legacy) = legacy {
+        crop.manager = legacy.manager;
+        crop.reward_treasury = legacy.reward_treasury;
+        crop.reward_mint = legacy.reward_mint;
+        crop.deposit_treasury = legacy.deposit_treasury;
+        crop.deposit_mint = legacy.deposit_mint;
+        crop.end_timestamp = legacy.end_timestamp;
+        crop.deposit_fee = legacy.deposit_fee;
+        crop.withdraw_fee = legacy.withdraw_fee;
+        crop.total_deposited = legacy.total_deposited;
+        crop.fees = legacy.fees;
+        crop.rewards_per_share = legacy.rewards_per_share;
+        crop.reward_rate = legacy.reward_rate;
+        crop.previous_reward_timestamp = legacy.previous_reward_timestamp;
+        crop.paused = legacy.paused;
+        crop.bump = legacy.bump;
+        crop.id = legacy.id;
+    }
+
+    if crop.reward_treasury != ctx.accounts.reward_treasury.key() {
+        return Err(FarmError::InvalidRewardTreasury.into());
+    }
+
+    if crop.version < 2 {
+        // Crops from before budgets paid out of their whole treasury, so they keep what it holds.
+        // Crops sharing a treasury each start with all of it, the operator can reallot from there.
+        crop.reward_budget = match crop.rewards_paid.checked_add(ctx.accounts.reward_treasury.amount) {
+            Some(x) => x,
+            None => return Err(FarmError::NumericalOverflowError.into()),
+        };
+
+        // Checkpoints start from the stake at migration, as earlier changes were never recorded
+        let current_timestamp = utils::get_current_timestamp()?;
+        let amount = crop.total_deposited;
+        utils::record_checkpoint(&mut crop.checkpoints, amount, current_timestamp);
+    }
+    crop.version = Crop::VERSION;
+    crop.exit(ctx.program_id)
+}
@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{Manager, ManagerV0};
+use crate::instructions::utils;
+
+// Unchecked because managers on an older layout fail to deserialize until they are grown
+#[derive(Accounts)]
+pub struct MigrateManager<'info> {
+
+    #[account(mut)]
+    pub manager: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    pub rent: Sysvar<'info, Rent>,
+
+}
+
+pub fn handler(ctx: Context<MigrateManager>) -> ProgramResult {
+    let info = &ctx.accounts.manager;
+    utils::assert_account_type::<Manager>(info, ctx.program_id)?;
+    let legacy = utils::read_version_zero::<ManagerV0>(info, ManagerV0::space())?;
+    utils::grow_account(
+        info,
+        &ctx.accounts.payer.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        &ctx.accounts.rent,
+        8 + Manager::space()
+    )?;
+    if legacy.is_some() {
+        utils::clear_account(info)?;
+    }
+
+    let mut manager = Account::<Manager>::try_from(info)?;
+    utils::assert_outdated(manager.version, Manager::VERSION)?;
+
+    // Version 0 managers only knew their owner and crops. They get the widest fee ceiling and the
+    // shortest timelock, as neither existed before. The owner can hedge the ceiling afterwards.
+    if let Some(legacy) = legacy {
+        manager.owner = legacy.owner;
+        manager.crops = legacy.crops;
+        manager.rewarder_bump = legacy.rewarder_bump;
+        manager.timelock_delay = utils::MIN_TIMELOCK_DELAY;
+        manager.max_fee = utils::MAX_FEE;
+    }
+    manager.version = Manager::VERSION;
+    manager.exit(ctx.program_id)
+}
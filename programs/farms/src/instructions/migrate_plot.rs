@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{Plot, PlotV0};
+use crate::instructions::utils;
+
+// Unchecked because plots on an older layout fail to deserialize until they are grown
+#[derive(Accounts)]
+pub struct MigratePlot<'info> {
+
+    #[account(mut)]
+    pub plot: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    pub rent: Sysvar<'info, Rent>,
+
+}
+
+pub fn handler(ctx: Context<MigratePlot>) -> ProgramResult {
+    let info = &ctx.accounts.plot;
+    utils::assert_account_type::<Plot>(info, ctx.program_id)?;
+    let legacy = utils::read_version_zero::<PlotV0>(info, PlotV0::space())?;
+    utils::grow_account(
+        info,
+        &ctx.accounts.payer.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        &ctx.accounts.rent,
+        8 + Plot::space()
+    )?;
+    if legacy.is_some() {
+        utils::clear_account(info)?;
+    }
+
+    let mut plot = Account::<Plot>::try_from(info)?;
+    utils::assert_outdated(plot.version, Plot::VERSION)?;
+
+    // Version 0 plots have been staked since before fees were shared, so they start without fee
+    // debt, and since before penalties, so they have no deposit timestamp either
+    if let Some(legacy) = legacy {
+        plot.amount = legacy.amount;
+        plot.debt = legacy.debt;
+        plot.bump = legacy.bump;
+    }
+
+    // Checkpoints start from the stake at migration, as earlier changes were never recorded
+    if plot.version < 2 {
+        let current_timestamp = utils::get_current_timestamp()?;
+        let amount = plot.amount;
+        utils::record_checkpoint(&mut plot.checkpoints, amount, current_timestamp);
+    }
+
+    // Version 3 appended the keeper, which the grown bytes leave unset
+    plot.version = Plot::VERSION;
+    plot.exit(ctx.program_id)
+}
@@ -0,0 +1,133 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+
+use crate::errors::FarmError;
+use crate::state::{Manager, Crop, Plot, Route};
+use crate::instructions::utils;
+
+// Moves stake between two crops of a manager taking the same deposit mint. The source plot's
+// rewards are settled and stay claimable there. Both crops' fees apply unless the route account
+// is the owner's route for the pair, in which case the stake also keeps its deposit age.
+#[derive(Accounts)]
+pub struct MigrateStake<'info> {
+
+    pub manager: Account<'info, Manager>,
+
+    #[account(
+        mut,
+        seeds = [b"crop".as_ref(), manager.key().as_ref(), &from_crop.id.to_le_bytes()],
+        bump = from_crop.bump,
+        has_one = manager,
+        constraint = from_crop.deposit_treasury == from_deposit_treasury.key()
+    )]
+    pub from_crop: Box<Account<'info, Crop>>,
+
+    #[account(
+        mut,
+        seeds = [b"plot".as_ref(), manager.key().as_ref(), farmer.key().as_ref(), &from_crop.id.to_le_bytes()],
+        bump = from_plot.bump
+    )]
+    pub from_plot: Box<Account<'info, Plot>>,
+
+    #[account(
+        mut,
+        seeds = [b"crop".as_ref(), manager.key().as_ref(), &to_crop.id.to_le_bytes()],
+        bump = to_crop.bump,
+        has_one = manager,
+        constraint = to_crop.key() != from_crop.key(),
+        constraint = to_crop.deposit_mint == from_crop.deposit_mint,
+        constraint = to_crop.deposit_treasury == to_deposit_treasury.key()
+    )]
+    pub to_crop: Box<Account<'info, Crop>>,
+
+    #[account(
+        mut,
+        seeds = [b"plot".as_ref(), manager.key().as_ref(), farmer.key().as_ref(), &to_crop.id.to_le_bytes()],
+        bump = to_plot.bump
+    )]
+    pub to_plot: Box<Account<'info, Plot>>,
+
+    #[account(mut)]
+    pub from_deposit_treasury: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub to_deposit_treasury: Account<'info, TokenAccount>,
+
+    // Any account when the pair has no route
+    pub route: AccountInfo<'info>,
+
+    pub farmer: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+
+}
+
+impl<'info> MigrateStake<'info> {
+
+    fn transfer_between_treasuries(&self, amount: u64) -> ProgramResult {
+        token::transfer(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                Transfer {
+                    from: self.from_deposit_treasury.to_account_info(),
+                    to: self.to_deposit_treasury.to_account_info(),
+                    authority: self.from_crop.to_account_info()
+                },
+                &[&[b"crop".as_ref(), self.from_crop.manager.as_ref(), &self.from_crop.id.to_le_bytes(), &[self.from_crop.bump]]]
+            ),
+            amount
+        )?;
+        Ok(())
+    }
+
+    fn is_route(&self, program_id: &Pubkey) -> Result<bool, ProgramError> {
+        if self.route.owner != program_id {
+            return Ok(false);
+        }
+        let route = Account::<Route>::try_from(&self.route)?;
+        if route.from_crop != self.from_crop.key() || route.to_crop != self.to_crop.key() {
+            return Err(FarmError::InvalidRoute.into());
+        }
+        Ok(true)
+    }
+
+}
+
+pub fn handler(ctx: Context<MigrateStake>, amount: u64) -> ProgramResult {
+    utils::assert_non_zero(amount)?;
+    utils::assert_enough_to_uproot(&ctx.accounts.from_plot, amount)?;
+    utils::assert_not_paused(&ctx.accounts.to_crop)?;
+    utils::assert_no_receipts(&ctx.accounts.from_crop)?;
+    utils::assert_no_receipts(&ctx.accounts.to_crop)?;
+    utils::assert_no_cooldown(&ctx.accounts.from_crop)?;
+
+    let current_timestamp = utils::get_current_timestamp()?;
+    let fee_free = ctx.accounts.is_route(ctx.program_id)?;
+
+    let accounts = &mut *ctx.accounts;
+    let (withdraw_fee, deposit_fee, deposit_timestamp) = if fee_free {
+        (0, 0, accounts.from_plot.deposit_timestamp)
+    } else {
+        let withdraw_fee = utils::calculate_withdraw_fee(&accounts.from_crop, &accounts.from_plot, current_timestamp)?;
+        (withdraw_fee, accounts.to_crop.deposit_fee, current_timestamp)
+    };
+
+    let withdrawal = utils::withdraw_at(
+        &mut accounts.from_crop,
+        &mut accounts.from_plot,
+        amount,
+        withdraw_fee,
+        current_timestamp
+    )?;
+    utils::deposit_at(
+        &mut accounts.to_crop,
+        &mut accounts.to_plot,
+        withdrawal.amount,
+        deposit_fee,
+        deposit_timestamp,
+        current_timestamp
+    )?;
+    accounts.transfer_between_treasuries(withdrawal.amount)?;
+
+    Ok(())
+}
@@ -1,21 +1,180 @@
 
 pub mod appoint;
 pub mod entrust;
+pub mod hedge;
 pub mod cultivate;
 pub mod recultivate;
 pub mod till;
 pub mod sow;
 pub mod uproot;
+pub mod request_uproot;
+pub mod cancel_uproot;
+pub mod harvest_many;
+pub mod hire;
+pub mod dismiss;
+pub mod glean;
+pub mod compound;
+pub mod migrate_stake;
+pub mod chart;
+pub mod unchart;
 pub mod collect;
+pub mod reap;
+pub mod consign;
+pub mod certify;
+pub mod reckon;
+pub mod enlist;
+pub mod redeem;
+pub mod grant;
+pub mod revoke;
+pub mod fallow;
+pub mod ripen;
+pub mod prune;
+pub mod appraise;
+pub mod weigh;
+pub mod tend;
+pub mod label;
+pub mod apportion;
+pub mod rotate;
+pub mod fertilize;
+pub mod irrigate;
+pub mod reallot;
+pub mod migrate_manager;
+pub mod migrate_crop;
+pub mod migrate_plot;
 pub mod utils;
 
-pub use appoint::*;
-pub use entrust::*;
-pub use cultivate::*;
-pub use recultivate::*;
-pub use till::*;
-pub use sow::*;
-pub use uproot::*;
-pub use collect::*;
-pub use utils::*;
+// Only the Accounts structs, every module has its own handler
+pub use appoint::Appoint;
+pub use entrust::Entrust;
+pub use hedge::Hedge;
+pub use cultivate::Cultivate;
+pub use recultivate::Recultivate;
+pub use till::Till;
+pub use sow::Sow;
+pub use uproot::Uproot;
+pub use request_uproot::RequestUproot;
+pub use cancel_uproot::CancelUproot;
+pub use harvest_many::HarvestMany;
+pub use hire::Hire;
+pub use dismiss::Dismiss;
+pub use glean::Glean;
+pub use compound::Compound;
+pub use migrate_stake::MigrateStake;
+pub use chart::Chart;
+pub use unchart::Unchart;
+pub use collect::Collect;
+pub use reap::Reap;
+pub use consign::Consign;
+pub use certify::Certify;
+pub use reckon::Reckon;
+pub use enlist::Enlist;
+pub use redeem::Redeem;
+pub use grant::Grant;
+pub use revoke::Revoke;
+pub use fallow::Fallow;
+pub use ripen::Ripen;
+pub use prune::Prune;
+pub use appraise::Appraise;
+pub use weigh::Weigh;
+pub use tend::Tend;
+pub use label::Label;
+pub use apportion::Apportion;
+pub use rotate::Rotate;
+pub use fertilize::Fertilize;
+pub use irrigate::Irrigate;
+pub use reallot::Reallot;
+pub use migrate_manager::MigrateManager;
+pub use migrate_crop::MigrateCrop;
+pub use migrate_plot::MigratePlot;
 
+// The program macro looks for the client modules generated next to each Accounts struct at the
+// crate root
+pub(crate) use self::{
+    appoint::__client_accounts_appoint,
+    entrust::__client_accounts_entrust,
+    hedge::__client_accounts_hedge,
+    cultivate::__client_accounts_cultivate,
+    recultivate::__client_accounts_recultivate,
+    till::__client_accounts_till,
+    sow::__client_accounts_sow,
+    uproot::__client_accounts_uproot,
+    request_uproot::__client_accounts_request_uproot,
+    cancel_uproot::__client_accounts_cancel_uproot,
+    harvest_many::__client_accounts_harvest_many,
+    hire::__client_accounts_hire,
+    dismiss::__client_accounts_dismiss,
+    glean::__client_accounts_glean,
+    compound::__client_accounts_compound,
+    migrate_stake::__client_accounts_migrate_stake,
+    chart::__client_accounts_chart,
+    unchart::__client_accounts_unchart,
+    collect::__client_accounts_collect,
+    reap::__client_accounts_reap,
+    consign::__client_accounts_consign,
+    certify::__client_accounts_certify,
+    reckon::__client_accounts_reckon,
+    enlist::__client_accounts_enlist,
+    redeem::__client_accounts_redeem,
+    grant::__client_accounts_grant,
+    revoke::__client_accounts_revoke,
+    fallow::__client_accounts_fallow,
+    ripen::__client_accounts_ripen,
+    prune::__client_accounts_prune,
+    appraise::__client_accounts_appraise,
+    weigh::__client_accounts_weigh,
+    tend::__client_accounts_tend,
+    label::__client_accounts_label,
+    apportion::__client_accounts_apportion,
+    rotate::__client_accounts_rotate,
+    fertilize::__client_accounts_fertilize,
+    irrigate::__client_accounts_irrigate,
+    reallot::__client_accounts_reallot,
+    migrate_manager::__client_accounts_migrate_manager,
+    migrate_crop::__client_accounts_migrate_crop,
+    migrate_plot::__client_accounts_migrate_plot,
+};
+#[cfg(feature = "cpi")]
+pub(crate) use self::{
+    appoint::__cpi_client_accounts_appoint,
+    entrust::__cpi_client_accounts_entrust,
+    hedge::__cpi_client_accounts_hedge,
+    cultivate::__cpi_client_accounts_cultivate,
+    recultivate::__cpi_client_accounts_recultivate,
+    till::__cpi_client_accounts_till,
+    sow::__cpi_client_accounts_sow,
+    uproot::__cpi_client_accounts_uproot,
+    request_uproot::__cpi_client_accounts_request_uproot,
+    cancel_uproot::__cpi_client_accounts_cancel_uproot,
+    harvest_many::__cpi_client_accounts_harvest_many,
+    hire::__cpi_client_accounts_hire,
+    dismiss::__cpi_client_accounts_dismiss,
+    glean::__cpi_client_accounts_glean,
+    compound::__cpi_client_accounts_compound,
+    migrate_stake::__cpi_client_accounts_migrate_stake,
+    chart::__cpi_client_accounts_chart,
+    unchart::__cpi_client_accounts_unchart,
+    collect::__cpi_client_accounts_collect,
+    reap::__cpi_client_accounts_reap,
+    consign::__cpi_client_accounts_consign,
+    certify::__cpi_client_accounts_certify,
+    reckon::__cpi_client_accounts_reckon,
+    enlist::__cpi_client_accounts_enlist,
+    redeem::__cpi_client_accounts_redeem,
+    grant::__cpi_client_accounts_grant,
+    revoke::__cpi_client_accounts_revoke,
+    fallow::__cpi_client_accounts_fallow,
+    ripen::__cpi_client_accounts_ripen,
+    prune::__cpi_client_accounts_prune,
+    appraise::__cpi_client_accounts_appraise,
+    weigh::__cpi_client_accounts_weigh,
+    tend::__cpi_client_accounts_tend,
+    label::__cpi_client_accounts_label,
+    apportion::__cpi_client_accounts_apportion,
+    rotate::__cpi_client_accounts_rotate,
+    fertilize::__cpi_client_accounts_fertilize,
+    irrigate::__cpi_client_accounts_irrigate,
+    reallot::__cpi_client_accounts_reallot,
+    migrate_manager::__cpi_client_accounts_migrate_manager,
+    migrate_crop::__cpi_client_accounts_migrate_crop,
+    migrate_plot::__cpi_client_accounts_migrate_plot,
+};
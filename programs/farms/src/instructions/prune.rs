@@ -0,0 +1,30 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{Manager, Crop};
+use crate::instructions::utils;
+
+#[derive(Accounts)]
+pub struct Prune<'info> {
+
+    #[account(constraint = manager.is_operator(&operator.key()))]
+    pub manager: Account<'info, Manager>,
+
+    #[account(
+        mut,
+        seeds = [b"crop".as_ref(), crop.manager.as_ref(), &crop.id.to_le_bytes()],
+        bump = crop.bump,
+        has_one = manager
+    )]
+    pub crop: Account<'info, Crop>,
+
+    pub operator: Signer<'info>,
+
+}
+
+pub fn handler(ctx: Context<Prune>) -> ProgramResult {
+    utils::assert_pending_update(&ctx.accounts.crop)?;
+
+    utils::clear_pending_update(&mut ctx.accounts.crop);
+
+    Ok(())
+}
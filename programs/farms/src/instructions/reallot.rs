@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::FarmError;
+use crate::state::{Manager, Crop};
+use crate::instructions::utils;
+
+// Moves unspent budget between two crops drawing on the same reward treasury
+#[derive(Accounts)]
+pub struct Reallot<'info> {
+
+    #[account(constraint = manager.is_operator(&operator.key()))]
+    pub manager: Account<'info, Manager>,
+
+    #[account(
+        mut,
+        seeds = [b"crop".as_ref(), manager.key().as_ref(), &from_crop.id.to_le_bytes()],
+        bump = from_crop.bump,
+        has_one = manager,
+    )]
+    pub from_crop: Box<Account<'info, Crop>>,
+
+    #[account(
+        mut,
+        seeds = [b"crop".as_ref(), manager.key().as_ref(), &to_crop.id.to_le_bytes()],
+        bump = to_crop.bump,
+        has_one = manager,
+        constraint = to_crop.key() != from_crop.key(),
+        constraint = to_crop.reward_treasury == from_crop.reward_treasury
+    )]
+    pub to_crop: Box<Account<'info, Crop>>,
+
+    pub operator: Signer<'info>,
+
+}
+
+pub fn handler(ctx: Context<Reallot>, amount: u64) -> ProgramResult {
+    utils::assert_non_zero(amount)?;
+    utils::assert_sufficient_budget(&ctx.accounts.from_crop, amount)?;
+
+    ctx.accounts.from_crop.reward_budget -= amount;
+
+    let to_crop = &mut ctx.accounts.to_crop;
+    to_crop.reward_budget = match to_crop.reward_budget.checked_add(amount) {
+        Some(x) => x,
+        None => return Err(FarmError::NumericalOverflowError.into()),
+    };
+
+    Ok(())
+}
@@ -0,0 +1,68 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+
+use crate::state::{Manager, Crop};
+use crate::instructions::utils;
+
+// Permissionless, so the destination has to belong to the fee recipient and collections have to
+// be due
+#[derive(Accounts)]
+pub struct Reap<'info> {
+
+    pub manager: Account<'info, Manager>,
+
+    #[account(
+        mut,
+        seeds = [b"crop".as_ref(), crop.manager.as_ref(), &crop.id.to_le_bytes()],
+        bump = crop.bump,
+        has_one = manager,
+        has_one = deposit_treasury
+    )]
+    pub crop: Account<'info, Crop>,
+
+    #[account(mut)]
+    pub deposit_treasury: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = crop.deposit_mint == deposit_token_account.mint.key()
+    )]
+    pub deposit_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+
+}
+
+impl<'info> Reap<'info> {
+
+    fn transfer_from_treasury(&self, amount: u64) -> ProgramResult {
+        token::transfer(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(), 
+                Transfer {
+                    from: self.deposit_treasury.to_account_info(),
+                    to: self.deposit_token_account.to_account_info(),
+                    authority: self.crop.to_account_info()
+                },
+                &[&[b"crop".as_ref(), self.crop.manager.as_ref(), &self.crop.id.to_le_bytes(), &[self.crop.bump]]]
+            ), 
+            amount
+        )?;
+        Ok(())
+    }
+
+}
+
+pub fn handler(ctx: Context<Reap>) -> ProgramResult {
+    let current_timestamp = utils::get_current_timestamp()?;
+
+    utils::assert_fee_destination(&ctx.accounts.manager, &ctx.accounts.deposit_token_account)?;
+    utils::assert_collection_due(&ctx.accounts.manager, &ctx.accounts.crop, current_timestamp)?;
+
+    let fees = ctx.accounts.crop.fees;
+    ctx.accounts.crop.fees = 0;
+    ctx.accounts.crop.previous_collect_timestamp = current_timestamp;
+    ctx.accounts.transfer_from_treasury(fees)?;
+
+    Ok(())
+}
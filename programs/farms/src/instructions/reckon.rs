@@ -0,0 +1,67 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::token::{Mint, TokenAccount};
+
+use crate::errors::FarmError;
+use crate::state::{Crop, Plot};
+use crate::instructions::utils;
+
+// Settles a plot's rewards and then sets its stake to the receipt balance of the farmer's
+// associated receipt token account. Anyone can bring a plot down to that balance, so whoever
+// receives receipts can settle the sender's plot before reckoning their own up, which only the
+// farmer can do since new stake counts as a fresh deposit for the withdraw penalty.
+#[derive(Accounts)]
+pub struct Reckon<'info> {
+
+    #[account(
+        mut,
+        seeds = [b"crop".as_ref(), crop.manager.as_ref(), &crop.id.to_le_bytes()],
+        bump = crop.bump
+    )]
+    pub crop: Account<'info, Crop>,
+
+    #[account(
+        mut,
+        seeds = [b"plot".as_ref(), crop.manager.as_ref(), farmer.key().as_ref(), &crop.id.to_le_bytes()],
+        bump = plot.bump
+    )]
+    pub plot: Account<'info, Plot>,
+
+    #[account(constraint = crop.receipt_mint == receipt_mint.key())]
+    pub receipt_mint: Account<'info, Mint>,
+
+    #[account(
+        constraint = receipt_token_account.key() == get_associated_token_address(&farmer.key(), &receipt_mint.key())
+    )]
+    pub receipt_token_account: Account<'info, TokenAccount>,
+
+    pub farmer: AccountInfo<'info>,
+
+    pub caller: Signer<'info>,
+
+}
+
+pub fn handler(ctx: Context<Reckon>) -> ProgramResult {
+
+    utils::assert_receipts_enabled(&ctx.accounts.crop)?;
+
+    // Follow the receipts
+    let amount = ctx.accounts.receipt_token_account.amount;
+    let increase = amount > ctx.accounts.plot.amount;
+    if increase && ctx.accounts.caller.key() != ctx.accounts.farmer.key() {
+        return Err(FarmError::OnlyFarmerCanReckonUp.into());
+    }
+    utils::restake(
+        &mut ctx.accounts.crop,
+        &mut ctx.accounts.plot,
+        amount,
+        utils::get_current_timestamp()?
+    )?;
+
+    // Receipts stay counted in the sender's plot until the sender reckons
+    if increase {
+        utils::assert_receipts_backed(&ctx.accounts.crop, ctx.accounts.receipt_mint.supply)?;
+    }
+
+    Ok(())
+}
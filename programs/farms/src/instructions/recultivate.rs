@@ -1,42 +1,79 @@
 use anchor_lang::prelude::*;
 
-use crate::state::{Manager, Crop};
+use crate::state::{Manager, Crop, CropParams, Penalty};
 use crate::instructions::utils;
 
 #[derive(Accounts)]
 pub struct Recultivate<'info> {
 
-    #[account(mut, has_one = owner)]
+    #[account(constraint = manager.is_operator(&operator.key()))]
     pub manager: Account<'info, Manager>,
 
     #[account(
         mut,
         seeds = [b"crop".as_ref(), crop.manager.as_ref(), &crop.id.to_le_bytes()],
-        bump = crop.bump
+        bump = crop.bump,
+        has_one = manager
     )]
     pub crop: Account<'info, Crop>,
 
-    pub owner: Signer<'info>,
+    pub operator: Signer<'info>,
 
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn handler(
     ctx: Context<Recultivate>, 
     deposit_fee: u64,
     withdraw_fee: u64,
+    referral_fee: u64,
     end_timestamp: u64,
-    reward_rate: u64
+    reward_rate: u64,
+    penalty: Penalty,
+    fee_share: u64,
+    cooldown: u64,
+    effective_timestamp: u64
 ) -> ProgramResult {
 
-    utils::assert_valid_fee(deposit_fee)?;
-    utils::assert_valid_fee(withdraw_fee)?;
+    let max_fee = ctx.accounts.manager.max_fee;
+    utils::assert_valid_fee(deposit_fee, max_fee)?;
+    utils::assert_valid_fee(withdraw_fee, max_fee)?;
+    utils::assert_valid_fee(referral_fee, max_fee)?;
+    utils::assert_valid_fee(penalty.max, max_fee)?;
 
-    // Construct the new crop
+    // Checked again when the update is applied, since farmers may have arrived by then
+    let crop = &ctx.accounts.crop;
+    utils::assert_valid_fee_increase(crop.deposit_fee, deposit_fee, crop.total_deposited)?;
+    utils::assert_valid_withdraw_fee_increase(
+        crop.withdraw_fee,
+        &crop.penalty,
+        withdraw_fee,
+        &penalty,
+        crop.total_deposited
+    )?;
+    utils::assert_valid_fee_increase(crop.referral_fee, referral_fee, crop.total_deposited)?;
+    utils::assert_valid_fee_share(crop.fee_share, fee_share, crop.total_deposited)?;
+    utils::assert_cooldown_compatible(cooldown, &crop.receipt_mint)?;
+
+    utils::assert_valid_delay(
+        utils::get_current_timestamp()?,
+        effective_timestamp,
+        ctx.accounts.manager.timelock_delay
+    )?;
+
+    // Queue the update, replacing any update that was already pending
     let crop = &mut ctx.accounts.crop;
-    crop.end_timestamp = end_timestamp;
-    crop.deposit_fee = deposit_fee;
-    crop.withdraw_fee = withdraw_fee;
-    crop.reward_rate = reward_rate;
+    crop.pending_params = CropParams {
+        deposit_fee,
+        withdraw_fee,
+        referral_fee,
+        end_timestamp,
+        reward_rate,
+    };
+    crop.pending_penalty = penalty;
+    crop.pending_fee_share = fee_share;
+    crop.pending_cooldown = cooldown;
+    crop.pending_timestamp = effective_timestamp;
 
     Ok(())
 }
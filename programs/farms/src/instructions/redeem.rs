@@ -0,0 +1,74 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+
+use crate::state::{Manager, Crop, Referral};
+
+#[derive(Accounts)]
+pub struct Redeem<'info> {
+
+    pub manager: Account<'info, Manager>,
+
+    #[account(
+        seeds = [b"rewarder".as_ref(), crop.manager.as_ref()],
+        bump = manager.rewarder_bump
+    )]
+    pub rewarder_pda: AccountInfo<'info>,
+
+    #[account(
+        seeds = [b"crop".as_ref(), crop.manager.as_ref(), &crop.id.to_le_bytes()],
+        bump = crop.bump,
+        has_one = manager,
+        has_one = reward_treasury
+    )]
+    pub crop: Account<'info, Crop>,
+
+    #[account(
+        mut,
+        seeds = [b"referral".as_ref(), crop.key().as_ref(), referrer.key().as_ref()],
+        bump = referral.bump
+    )]
+    pub referral: Account<'info, Referral>,
+
+    #[account(mut)]
+    pub reward_treasury: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = crop.reward_mint == reward_token_account.mint.key()
+    )]
+    pub reward_token_account: Account<'info, TokenAccount>,
+
+    pub referrer: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+
+}
+
+impl<'info> Redeem<'info> {
+
+    fn transfer_rewards(&self, amount: u64) -> ProgramResult {
+        token::transfer(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(), 
+                Transfer {
+                    from: self.reward_treasury.to_account_info(),
+                    to: self.reward_token_account.to_account_info(),
+                    authority: self.rewarder_pda.to_account_info()
+                },
+                &[&[b"rewarder".as_ref(), self.crop.manager.as_ref(), &[self.manager.rewarder_bump]]]
+            ), 
+            amount
+        )?;
+        Ok(())
+    }
+
+}
+
+pub fn handler(ctx: Context<Redeem>) -> ProgramResult {
+
+    let kickback = ctx.accounts.referral.unclaimed;
+    ctx.accounts.referral.unclaimed = 0;
+    ctx.accounts.transfer_rewards(kickback)?;
+
+    Ok(())
+}
@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{Crop, Plot};
+use crate::instructions::utils;
+
+// The requested stake stops earning right away and can be uprooted once the crop's cooldown has
+// passed. Its rewards so far stay claimable on the plot.
+#[derive(Accounts)]
+pub struct RequestUproot<'info> {
+
+    #[account(
+        mut,
+        seeds = [b"crop".as_ref(), crop.manager.as_ref(), &crop.id.to_le_bytes()],
+        bump = crop.bump
+    )]
+    pub crop: Account<'info, Crop>,
+
+    #[account(
+        mut,
+        seeds = [b"plot".as_ref(), crop.manager.as_ref(), farmer.key().as_ref(), &crop.id.to_le_bytes()],
+        bump = plot.bump
+    )]
+    pub plot: Account<'info, Plot>,
+
+    pub farmer: Signer<'info>,
+
+}
+
+pub fn handler(ctx: Context<RequestUproot>, amount: u64) -> ProgramResult {
+    utils::assert_cooldown(&ctx.accounts.crop)?;
+    utils::assert_non_zero(amount)?;
+    utils::assert_enough_to_uproot(&ctx.accounts.plot, amount)?;
+
+    utils::request_withdrawal(
+        &mut ctx.accounts.crop,
+        &mut ctx.accounts.plot,
+        amount,
+        utils::get_current_timestamp()?
+    )
+}
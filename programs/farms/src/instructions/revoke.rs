@@ -0,0 +1,26 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{Manager, Role};
+
+#[derive(Accounts)]
+pub struct Revoke<'info> {
+
+    #[account(
+        mut,
+        has_one = owner
+    )]
+    pub manager: Account<'info, Manager>,
+
+    pub owner: Signer<'info>,
+
+}
+
+pub fn handler(ctx: Context<Revoke>, role: Role) -> ProgramResult {
+    let manager = &mut ctx.accounts.manager;
+    match role {
+        Role::Operator => manager.operator = Pubkey::default(),
+        Role::FeeCollector => manager.fee_collector = Pubkey::default(),
+        Role::Pauser => manager.pauser = Pubkey::default(),
+    }
+    Ok(())
+}
@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::FarmError;
+use crate::state::{Manager, Crop};
+use crate::instructions::utils;
+
+// Applies the crop's queued update once it is effective. Apportionments are rotated in instead.
+#[derive(Accounts)]
+pub struct Ripen<'info> {
+
+    pub manager: Account<'info, Manager>,
+
+    #[account(
+        mut,
+        seeds = [b"crop".as_ref(), crop.manager.as_ref(), &crop.id.to_le_bytes()],
+        bump = crop.bump,
+        has_one = manager,
+    )]
+    pub crop: Account<'info, Crop>,
+
+}
+
+pub fn handler(ctx: Context<Ripen>) -> ProgramResult {
+    let current_timestamp = utils::get_current_timestamp()?;
+
+    let crop = &ctx.accounts.crop;
+    if crop.pending_timestamp == 0 {
+        return Err(FarmError::NoPendingUpdate.into());
+    }
+    if current_timestamp < crop.pending_timestamp {
+        return Err(FarmError::UpdateNotEffective.into());
+    }
+
+    // Distribute everything owed under the old schedule before switching over. If the old
+    // schedule already ended, nothing was owed for the gap since then.
+    let crop = &mut ctx.accounts.crop;
+    utils::update_crop(crop)?;
+    crop.previous_reward_timestamp = current_timestamp;
+
+    let params = crop.pending_params;
+    utils::assert_valid_fee_increase(crop.deposit_fee, params.deposit_fee, crop.total_deposited)?;
+    utils::assert_valid_withdraw_fee_increase(
+        crop.withdraw_fee,
+        &crop.penalty,
+        params.withdraw_fee,
+        &crop.pending_penalty,
+        crop.total_deposited
+    )?;
+    utils::assert_valid_fee_increase(crop.referral_fee, params.referral_fee, crop.total_deposited)?;
+    utils::assert_valid_fee_share(crop.fee_share, crop.pending_fee_share, crop.total_deposited)?;
+    utils::assert_cooldown_compatible(crop.pending_cooldown, &crop.receipt_mint)?;
+
+    crop.deposit_fee = params.deposit_fee;
+    crop.withdraw_fee = params.withdraw_fee;
+    crop.referral_fee = params.referral_fee;
+    crop.end_timestamp = params.end_timestamp;
+    crop.penalty = crop.pending_penalty;
+    crop.fee_share = crop.pending_fee_share;
+    crop.cooldown = crop.pending_cooldown;
+
+    // An apportioned crop's rate follows its allocation points
+    if !ctx.accounts.manager.is_apportioned() {
+        crop.reward_rate = params.reward_rate;
+    }
+
+    utils::clear_pending_update(crop);
+
+    Ok(())
+}
@@ -0,0 +1,76 @@
+use std::cmp;
+
+use anchor_lang::prelude::*;
+
+use crate::errors::FarmError;
+use crate::state::{Manager, Crop};
+use crate::instructions::utils;
+
+// Switches a run of crops over to the queued apportionment once it is effective. Each crop earns
+// its old rate up to the effective timestamp and its new rate from then on, however late it is
+// rotated, unless something settled it at the old rate in between. Anyone can rotate, the crops
+// follow as remaining accounts in id order from the first crop not rotated yet. The manager takes
+// the new emission rate together with the last crop, and the queued apportionment is cleared.
+#[derive(Accounts)]
+pub struct Rotate<'info> {
+
+    #[account(mut)]
+    pub manager: Account<'info, Manager>,
+
+}
+
+pub fn handler<'info>(ctx: Context<'_, '_, '_, 'info, Rotate<'info>>) -> ProgramResult {
+    let current_timestamp = utils::get_current_timestamp()?;
+
+    let manager = &mut ctx.accounts.manager;
+    if manager.pending_apportion_timestamp == 0 {
+        return Err(FarmError::NoPendingUpdate.into());
+    }
+    if !manager.is_apportionment_ready(current_timestamp) {
+        return Err(FarmError::UpdateNotEffective.into());
+    }
+    if ctx.remaining_accounts.is_empty() {
+        return Err(FarmError::MissingCrops.into());
+    }
+
+    for info in ctx.remaining_accounts.iter() {
+        let mut crop = Account::<Crop>::try_from(info)?;
+        if crop.manager != manager.key() || crop.id != manager.rotated_crops {
+            return Err(FarmError::MissingCrops.into());
+        }
+
+        // Distribute everything owed under the old rate before switching over
+        let effective_timestamp = manager.pending_apportion_timestamp;
+        utils::update_crop_at(&mut crop, effective_timestamp)?;
+        crop.previous_reward_timestamp = cmp::max(crop.previous_reward_timestamp, effective_timestamp);
+
+        // Crops cultivated after the apportionment was queued have no points in it
+        crop.alloc_points = crop.pending_alloc_points;
+        crop.reward_rate = utils::apportion_rate(
+            manager.pending_emission_rate,
+            crop.alloc_points,
+            manager.pending_total_alloc_points
+        )?;
+        crop.pending_alloc_points = 0;
+        crop.pending_apportion = false;
+
+        // If the schedule already ended, nothing was owed for the gap since then
+        utils::update_crop_at(&mut crop, current_timestamp)?;
+        crop.previous_reward_timestamp = current_timestamp;
+        crop.exit(ctx.program_id)?;
+
+        manager.rotated_crops += 1;
+    }
+
+    if manager.rotated_crops == manager.crops {
+        manager.emission_rate = manager.pending_emission_rate;
+        manager.total_alloc_points = manager.pending_total_alloc_points;
+        manager.pending_emission_rate = 0;
+        manager.pending_total_alloc_points = 0;
+        manager.apportioned_crops = 0;
+        manager.pending_apportion_timestamp = 0;
+        manager.rotated_crops = 0;
+    }
+
+    Ok(())
+}
@@ -1,7 +1,8 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
-
+use anchor_lang::solana_program::account_info::next_account_info;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer, MintTo};
 
+use crate::errors::FarmError;
 use crate::state::{Crop, Plot};
 use crate::instructions::utils;
 
@@ -14,7 +15,7 @@ pub struct Sow<'info> {
         bump = crop.bump,
         has_one = deposit_treasury,
     )]
-    pub crop: Account<'info, Crop>,
+    pub crop: Box<Account<'info, Crop>>,
 
     #[account(
         mut,
@@ -55,34 +56,80 @@ impl<'info> Sow<'info> {
         Ok(())
     }
 
+    fn mint_receipts(
+        &self,
+        receipt_mint: &AccountInfo<'info>,
+        receipt_token_account: &AccountInfo<'info>,
+        amount: u64
+    ) -> ProgramResult {
+        token::mint_to(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(), 
+                MintTo {
+                    mint: receipt_mint.clone(),
+                    to: receipt_token_account.clone(),
+                    authority: self.crop.to_account_info()
+                },
+                &[&[b"crop".as_ref(), self.crop.manager.as_ref(), &self.crop.id.to_le_bytes(), &[self.crop.bump]]]
+            ), 
+            amount
+        )?;
+        Ok(())
+    }
+
 }
 
-pub fn handler(ctx: Context<Sow>, amount: u64) -> ProgramResult {
+pub fn handler<'info>(ctx: Context<'_, '_, '_, 'info, Sow<'info>>, amount: u64, referrer: Option<Pubkey>) -> ProgramResult {
     let from_token_account = &ctx.accounts.from_token_account;
 
+    utils::assert_not_paused(&ctx.accounts.crop)?;
     utils::assert_non_zero(amount)?;
     utils::assert_sufficient_funds(from_token_account, amount)?;
 
     // transfer the user's tokens to the treasury
     ctx.accounts.transfer_to_treasury(amount)?;
 
-    // handle deposit fee
-    let fee_amount = utils::calculate_fee(ctx.accounts.crop.deposit_fee, amount)?;
-    let deposit_amount = amount - fee_amount;
-
-    // // update last reward 
-    utils::update_crop(&mut ctx.accounts.crop)?;
-    
-    // Update the users information
-    ctx.accounts.plot.debt += utils::calculate_reward_debt(
-        ctx.accounts.crop.rewards_per_share,
-        deposit_amount
+    // Update the farm and the users information, keeping the deposit fee aside
+    let deposit = utils::deposit(
+        &mut ctx.accounts.crop,
+        &mut ctx.accounts.plot,
+        amount,
+        utils::get_current_timestamp()?
     )?;
-    ctx.accounts.plot.amount += deposit_amount;
 
-    // Update the farm
-    ctx.accounts.crop.total_deposited += deposit_amount;
-    ctx.accounts.crop.fees += fee_amount;
+    // Crops that issue receipts expect the receipt mint and the farmer's associated receipt token account
+    // as the first remaining accounts
+    let remaining = &mut ctx.remaining_accounts.iter();
+    if ctx.accounts.crop.receipt_mint != Pubkey::default() {
+        let receipt_mint = next_account_info(remaining)?;
+        let receipt_token_account = next_account_info(remaining)?;
+        utils::assert_receipt_accounts(
+            &ctx.accounts.crop,
+            receipt_mint.key,
+            receipt_token_account.key,
+            &ctx.accounts.farmer.key()
+        )?;
+        ctx.accounts.mint_receipts(receipt_mint, receipt_token_account, deposit.amount)?;
+    }
+
+    // The first referrer sticks to the plot. Their referral account comes next in the remaining
+    // accounts so we know they have enlisted before attributing the plot to them.
+    if let Some(referrer) = referrer {
+        if ctx.accounts.plot.referrer == Pubkey::default() {
+            utils::assert_valid_referrer(&referrer, &ctx.accounts.farmer.key())?;
+            let mut referral = utils::load_referral(
+                next_account_info(remaining)?,
+                &ctx.accounts.crop.key(),
+                &referrer
+            )?;
+            referral.referred = match referral.referred.checked_add(1) {
+                Some(x) => x,
+                None => return Err(FarmError::NumericalOverflowError.into())
+            };
+            referral.exit(ctx.program_id)?;
+            ctx.accounts.plot.referrer = referrer;
+        }
+    }
 
     Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+
+use crate::state::Crop;
+use crate::events::Tended;
+use crate::instructions::utils;
+
+// Anyone can bring a crop's accumulator up to date, so it does not go stale while nobody sows
+// or uproots
+#[derive(Accounts)]
+pub struct Tend<'info> {
+
+    #[account(
+        mut,
+        seeds = [b"crop".as_ref(), crop.manager.as_ref(), &crop.id.to_le_bytes()],
+        bump = crop.bump
+    )]
+    pub crop: Box<Account<'info, Crop>>,
+
+}
+
+pub fn handler(ctx: Context<Tend>) -> ProgramResult {
+    let crop = &mut ctx.accounts.crop;
+    utils::update_crop(crop)?;
+
+    emit!(Tended {
+        crop: crop.key(),
+        rewards_per_share: crop.rewards_per_share,
+        previous_reward_timestamp: crop.previous_reward_timestamp,
+    });
+
+    Ok(())
+}
@@ -28,5 +28,6 @@ pub struct Till<'info> {
 
 pub fn handler(ctx: Context<Till>, seed: u8) -> ProgramResult {
     ctx.accounts.plot.bump = seed;
+    ctx.accounts.plot.version = Plot::VERSION;
     Ok(())
 }
\ No newline at end of file
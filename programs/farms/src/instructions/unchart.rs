@@ -0,0 +1,29 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{Manager, Crop, Route};
+
+#[derive(Accounts)]
+pub struct Unchart<'info> {
+
+    #[account(has_one = owner)]
+    pub manager: Account<'info, Manager>,
+
+    #[account(has_one = manager)]
+    pub from_crop: Account<'info, Crop>,
+
+    #[account(
+        mut,
+        close = owner,
+        seeds = [b"route".as_ref(), from_crop.key().as_ref(), route.to_crop.as_ref()],
+        bump = route.bump
+    )]
+    pub route: Account<'info, Route>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+}
+
+pub fn handler(_ctx: Context<Unchart>) -> ProgramResult {
+    Ok(())
+}
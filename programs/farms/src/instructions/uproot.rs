@@ -1,7 +1,9 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use anchor_lang::solana_program::account_info::next_account_info;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer, Burn};
 
 
+use crate::errors::FarmError;
 use crate::state::{Manager, Crop, Plot};
 use crate::instructions::utils;
 
@@ -91,36 +93,98 @@ impl<'info> Uproot<'info> {
         Ok(())
     }
 
+    fn burn_receipts(
+        &self,
+        receipt_mint: &AccountInfo<'info>,
+        receipt_token_account: &AccountInfo<'info>,
+        amount: u64
+    ) -> ProgramResult {
+        token::burn(
+            CpiContext::new(
+                self.token_program.to_account_info(), 
+                Burn {
+                    mint: receipt_mint.clone(),
+                    to: receipt_token_account.clone(),
+                    authority: self.farmer.to_account_info()
+                }
+            ), 
+            amount
+        )?;
+        Ok(())
+    }
+
 }
 
-pub fn handler(ctx: Context<Uproot>, amount: u64) -> ProgramResult {
+pub fn handler<'info>(ctx: Context<'_, '_, '_, 'info, Uproot<'info>>, amount: u64) -> ProgramResult {
 
-    msg!("amount: {}", ctx.accounts.plot.amount);
+    // Crops with a cooldown only pay out stake that has gone through it, which release checks.
+    // Stake requested before the cooldown was turned off comes out first.
+    let cooling = ctx.accounts.crop.cooldown > 0 || ctx.accounts.plot.unbonding_amount > 0;
 
     // check amount isnt more than plot has
-    utils::assert_enough_to_uproot(&ctx.accounts.plot, amount)?;
-
-    utils::update_crop(&mut ctx.accounts.crop)?;
+    if !cooling {
+        utils::assert_enough_to_uproot(&ctx.accounts.plot, amount)?;
+    }
 
-    // Transfer rewards?
-    let rewards = utils::calculate_plot_rewards(&ctx.accounts.crop, &ctx.accounts.plot)?;
+    // Crops that issue receipts expect the receipt mint and the farmer's associated receipt token account
+    // as the first remaining accounts when withdrawing
+    let remaining = &mut ctx.remaining_accounts.iter();
+    let receipt_accounts = if amount > 0 && ctx.accounts.crop.receipt_mint != Pubkey::default() {
+        let receipt_mint = next_account_info(remaining)?;
+        let receipt_token_account = next_account_info(remaining)?;
+        utils::assert_receipt_accounts(
+            &ctx.accounts.crop,
+            receipt_mint.key,
+            receipt_token_account.key,
+            &ctx.accounts.farmer.key()
+        )?;
+        Some((receipt_mint, receipt_token_account))
+    } else {
+        None
+    };
+
+    let current_timestamp = utils::get_current_timestamp()?;
+
+    // Transfer rewards, less the kickback owed to the plot's referrer whose referral account
+    // follows any receipt accounts
+    let rewards = utils::harvest(&mut ctx.accounts.crop, &mut ctx.accounts.plot, current_timestamp)?;
+    let rewards = utils::draw_budget(&mut ctx.accounts.crop, &mut ctx.accounts.plot, rewards)?;
+    let (rewards, kickback) = utils::split_referral(&ctx.accounts.crop, &ctx.accounts.plot, rewards)?;
+    if ctx.accounts.plot.referrer != Pubkey::default() {
+        let mut referral = utils::load_referral(
+            next_account_info(remaining)?,
+            &ctx.accounts.crop.key(),
+            &ctx.accounts.plot.referrer
+        )?;
+        utils::credit_referral(&mut referral, kickback)?;
+        referral.exit(ctx.program_id)?;
+    }
     ctx.accounts.transfer_rewards(rewards)?;
 
+    let mut payout = 0u64;
     if amount > 0 {
 
-        ctx.accounts.plot.amount -= amount;
-        ctx.accounts.plot.debt = utils::calculate_reward_debt(
-            ctx.accounts.crop.rewards_per_share,
-            ctx.accounts.plot.amount
-        )?;
-
-        ctx.accounts.crop.total_deposited -= amount;
-
-        let fee_amount = utils::calculate_fee(ctx.accounts.crop.withdraw_fee, amount)?;
-        let withdraw_amount = amount - fee_amount;
+        if let Some((receipt_mint, receipt_token_account)) = receipt_accounts {
+            ctx.accounts.burn_receipts(receipt_mint, receipt_token_account, amount)?;
+        }
+
+        // Update the farm and the users information, keeping the withdraw fee aside
+        let withdrawal = if cooling {
+            utils::release(&mut ctx.accounts.crop, &mut ctx.accounts.plot, amount, current_timestamp)?
+        } else {
+            utils::withdraw(&mut ctx.accounts.crop, &mut ctx.accounts.plot, amount, current_timestamp)?
+        };
+        payout = withdrawal.amount;
+    }
 
-        ctx.accounts.transfer_from_treasury(withdraw_amount)?;
-        ctx.accounts.crop.fees += fee_amount;
+    // The farmer's share of fees comes out of the deposit treasury along with the withdrawal
+    let fees = utils::claim_fees(&mut ctx.accounts.crop, &mut ctx.accounts.plot)?;
+    payout = match payout.checked_add(fees) {
+        Some(x) => x,
+        None => return Err(FarmError::NumericalOverflowError.into()),
+    };
+    if payout > 0 {
+        ctx.accounts.transfer_from_treasury(payout)?;
     }
 
     Ok(())
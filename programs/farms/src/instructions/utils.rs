@@ -1,96 +1,379 @@
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::sysvar::clock::Clock;
+use anchor_lang::solana_program::{program::{invoke, invoke_signed}, system_instruction};
+use anchor_lang::Discriminator;
+use anchor_spl::associated_token::get_associated_token_address;
 use anchor_spl::token::TokenAccount;
+use std::cmp;
 use std::result::Result;
 use num_traits::Zero;
-use std::cmp;
+use farms_accounting::{self as accounting, Pool, Position, Deposit, Withdrawal};
 
 use crate::errors::*;
-use crate::state::{Crop, Plot};
+use crate::state::{Manager, Crop, CropParams, Plot, Checkpoint, KeeperScope, Referral, Metadata, Penalty};
 
 type IntegerResult = Result<u64, ProgramError>;
 
-pub const MAX_FEE: u64 = 1_000_000_000;
-pub const ONE_REWARD: u64 = 1_000_000_000;
+pub use farms_accounting::{MAX_FEE, ONE_REWARD};
+// Largest increase a single update can make to a fee while farmers are staked (1%)
+pub const MAX_FEE_INCREASE: u64 = 10_000_000;
+// Shortest timelock a manager can have, so those increases are at least a day apart
+pub const MIN_TIMELOCK_DELAY: u64 = 86_400;
 
 pub fn get_current_timestamp() -> IntegerResult {
     Ok(Clock::get()?.unix_timestamp as u64)
 }
 
+// The accounting itself lives in farms-accounting so off-chain tools get the same numbers. These
+// move crops and plots in and out of its plain structs.
+
+pub fn pool(crop: &Crop) -> Pool {
+    Pool {
+        total_deposited: crop.total_deposited,
+        fees: crop.fees,
+        rewards_per_share: crop.rewards_per_share,
+        reward_rate: crop.reward_rate,
+        end_timestamp: crop.end_timestamp,
+        previous_reward_timestamp: crop.previous_reward_timestamp,
+        fee_share: crop.fee_share,
+        fees_per_share: crop.fees_per_share,
+        staker_fees: crop.staker_fees,
+    }
+}
+
+pub fn store_pool(crop: &mut Crop, pool: &Pool) {
+    crop.total_deposited = pool.total_deposited;
+    crop.fees = pool.fees;
+    crop.rewards_per_share = pool.rewards_per_share;
+    crop.previous_reward_timestamp = pool.previous_reward_timestamp;
+    crop.fees_per_share = pool.fees_per_share;
+    crop.staker_fees = pool.staker_fees;
+}
+
+pub fn position(plot: &Plot) -> Position {
+    Position {
+        amount: plot.amount,
+        debt: plot.debt,
+        unclaimed: plot.unclaimed,
+        fee_debt: plot.fee_debt,
+        unclaimed_fees: plot.unclaimed_fees,
+    }
+}
+
+pub fn store_position(plot: &mut Plot, position: &Position) {
+    plot.amount = position.amount;
+    plot.debt = position.debt;
+    plot.unclaimed = position.unclaimed;
+    plot.fee_debt = position.fee_debt;
+    plot.unclaimed_fees = position.unclaimed_fees;
+}
+
 pub fn calculate_fee(fee: u64, amount: u64) -> IntegerResult  {
-    Ok(match (amount as u128).checked_mul(fee as u128) {
-        Some(x) => match x.checked_div(MAX_FEE as u128) {
-            Some(y) => y as u64,
-            None => return Err(FarmError::NumericalOverflowError.into()),
-        },
-        None => return Err(FarmError::NumericalOverflowError.into()),
-    })
+    Ok(accounting::calculate_fee(fee, amount).map_err(FarmError::from)?)
 }
 
 pub fn calculate_reward_debt(rewards_per_share: u64, amount: u64) -> IntegerResult {
-    Ok(match (amount as u128).checked_mul(rewards_per_share as u128) {
-        Some(x) => match x.checked_div(ONE_REWARD as u128) {
-            Some(y) => y as u64,
-            None => return Err(FarmError::NumericalOverflowError.into()),
-        },
-        None => return Err(FarmError::NumericalOverflowError.into()),
-    })
+    Ok(accounting::calculate_reward_debt(rewards_per_share, amount).map_err(FarmError::from)?)
 }
 
-pub fn calculate_additional_rewards(
-    seconds: u64, 
-    rewards_per_second: u64,
-    total_deposited: u64
-) -> IntegerResult {
-    
-    let rewards = match (seconds as u128).checked_mul(rewards_per_second as u128) {
-        Some(x) => x as u128,
+pub fn calculate_plot_rewards(crop: &Crop, plot: &Plot) -> IntegerResult {
+    Ok(position(plot).pending(&pool(crop)).map_err(FarmError::from)?)
+}
+
+// Splits harvested rewards into the farmer's share and the referrer's kickback
+pub fn split_referral(crop: &Crop, plot: &Plot, rewards: u64) -> Result<(u64, u64), ProgramError> {
+    if plot.referrer == Pubkey::default() {
+        return Ok((rewards, 0));
+    }
+    Ok(accounting::split_referral(crop.referral_fee, rewards).map_err(FarmError::from)?)
+}
+
+// Move the plot's pending rewards and fees into its unclaimed balances and reset its debts
+// against the crop's current rewards and fees per share. The crop must be up to date before calling this.
+pub fn settle_plot(crop: &Crop, plot: &mut Plot) -> ProgramResult {
+    let mut position = position(plot);
+    position.settle(&pool(crop)).map_err(FarmError::from)?;
+    store_position(plot, &position);
+    Ok(())
+}
+
+pub fn update_crop(crop: &mut Crop) -> ProgramResult {
+    update_crop_at(crop, get_current_timestamp()?)
+}
+
+pub fn update_crop_at(crop: &mut Crop, current_timestamp: u64) -> ProgramResult {
+    let mut pool = pool(crop);
+    pool.accrue(current_timestamp).map_err(FarmError::from)?;
+    store_pool(crop, &pool);
+    Ok(())
+}
+
+// Unlike store_pool this sets the crop's schedule, which only the operator otherwise changes
+pub fn extend_rewards(crop: &mut Crop, amount: u64, duration: u64, current_timestamp: u64) -> ProgramResult {
+    let mut pool = pool(crop);
+    pool.extend_rewards(amount, duration, current_timestamp).map_err(FarmError::from)?;
+    store_pool(crop, &pool);
+    crop.reward_rate = pool.reward_rate;
+    crop.end_timestamp = pool.end_timestamp;
+    Ok(())
+}
+
+pub fn deposit(
+    crop: &mut Crop,
+    plot: &mut Plot,
+    amount: u64,
+    current_timestamp: u64
+) -> Result<Deposit, ProgramError> {
+    let deposit_fee = crop.deposit_fee;
+    deposit_at(crop, plot, amount, deposit_fee, current_timestamp, current_timestamp)
+}
+
+// A deposit at the given fee, of stake that counts as deposited at deposit_timestamp for the
+// withdraw penalty
+pub fn deposit_at(
+    crop: &mut Crop,
+    plot: &mut Plot,
+    amount: u64,
+    deposit_fee: u64,
+    deposit_timestamp: u64,
+    current_timestamp: u64
+) -> Result<Deposit, ProgramError> {
+    let (mut pool, mut position) = (pool(crop), position(plot));
+    let deposit = accounting::deposit(&mut pool, &mut position, amount, deposit_fee, current_timestamp)
+        .map_err(FarmError::from)?;
+    plot.deposit_timestamp = accounting::weighted_deposit_timestamp(
+        plot.amount,
+        plot.deposit_timestamp,
+        deposit.amount,
+        deposit_timestamp
+    ).map_err(FarmError::from)?;
+    store_pool(crop, &pool);
+    store_position(plot, &position);
+    record_stake(crop, plot, current_timestamp);
+    Ok(deposit)
+}
+
+// Whatever is left of the penalty for the plot's deposit age, or the flat withdraw fee for a crop
+// without one. Either is within the manager's max fee.
+pub fn calculate_withdraw_fee(crop: &Crop, plot: &Plot, current_timestamp: u64) -> IntegerResult {
+    let age = current_timestamp.saturating_sub(plot.deposit_timestamp);
+    withdraw_fee_at_age(crop.withdraw_fee, &crop.penalty, age)
+}
+
+fn withdraw_fee_at_age(withdraw_fee: u64, penalty: &Penalty, age: u64) -> IntegerResult {
+    if !penalty.is_set() {
+        return Ok(withdraw_fee);
+    }
+    let fee = accounting::calculate_penalty_fee(penalty.max, penalty.period, age).map_err(FarmError::from)?;
+    Ok(fee)
+}
+
+pub fn withdraw(
+    crop: &mut Crop,
+    plot: &mut Plot,
+    amount: u64,
+    current_timestamp: u64
+) -> Result<Withdrawal, ProgramError> {
+    let withdraw_fee = calculate_withdraw_fee(crop, plot, current_timestamp)?;
+    withdraw_at(crop, plot, amount, withdraw_fee, current_timestamp)
+}
+
+pub fn withdraw_at(
+    crop: &mut Crop,
+    plot: &mut Plot,
+    amount: u64,
+    withdraw_fee: u64,
+    current_timestamp: u64
+) -> Result<Withdrawal, ProgramError> {
+    let (mut pool, mut position) = (pool(crop), position(plot));
+    let withdrawal = accounting::withdraw(&mut pool, &mut position, amount, withdraw_fee, current_timestamp)
+        .map_err(FarmError::from)?;
+    store_pool(crop, &pool);
+    store_position(plot, &position);
+    record_stake(crop, plot, current_timestamp);
+    Ok(withdrawal)
+}
+
+// Takes stake out of the crop without a fee and starts its cooldown. Requesting more restarts
+// the cooldown for all of it.
+pub fn request_withdrawal(
+    crop: &mut Crop,
+    plot: &mut Plot,
+    amount: u64,
+    current_timestamp: u64
+) -> ProgramResult {
+    withdraw_at(crop, plot, amount, 0, current_timestamp)?;
+    plot.unbonding_amount = match plot.unbonding_amount.checked_add(amount) {
+        Some(x) => x,
         None => return Err(FarmError::NumericalOverflowError.into()),
     };
-    Ok(match (rewards as u128).checked_mul(ONE_REWARD as u128) {
-        Some(x) => match x.checked_div(total_deposited as u128) {
-            Some(y) => y as u64,
-            None => return Err(FarmError::NumericalOverflowError.into()),
-        },
+    plot.unbonding_timestamp = match current_timestamp.checked_add(crop.cooldown) {
+        Some(x) => x,
         None => return Err(FarmError::NumericalOverflowError.into()),
-    }) 
+    };
+    Ok(())
 }
 
-pub fn calculate_plot_rewards(crop: &Crop, plot: &Plot) -> IntegerResult {
-    Ok(match (plot.amount as u128).checked_mul(crop.rewards_per_share as u128) {
-        Some(x) => match x.checked_sub(plot.debt as u128) {
-            Some(y) => y as u64,
-            None => return Err(FarmError::NumericalOverflowError.into()),
-        },
-        None => return Err(FarmError::NumericalOverflowError.into()),
-    })
+// Puts all unbonding stake back without a fee, keeping its deposit age
+pub fn cancel_withdrawal(crop: &mut Crop, plot: &mut Plot, current_timestamp: u64) -> ProgramResult {
+    let amount = plot.unbonding_amount;
+    let deposit_timestamp = plot.deposit_timestamp;
+    deposit_at(crop, plot, amount, 0, deposit_timestamp, current_timestamp)?;
+    plot.unbonding_amount = 0;
+    plot.unbonding_timestamp = 0;
+    Ok(())
 }
 
-pub fn update_crop(crop: &mut Crop) -> ProgramResult {
-    let current_timestamp = get_current_timestamp()?;
-    if current_timestamp > crop.previous_reward_timestamp {
-
-        // Check how many seconds have passed since the last reward update, but make sure to not go
-        // past the configured end timestamp.
-        let current_reward_timestamp = cmp::min(current_timestamp, crop.end_timestamp);
-        let time_elapsed = current_reward_timestamp - crop.previous_reward_timestamp;
-
-        // Only update the rewards if there are at least some tokens deposited
-        if crop.total_deposited > 0 {
-            crop.rewards_per_share += calculate_additional_rewards(
-                time_elapsed, 
-                crop.reward_rate,
-                crop.total_deposited
-            )?;
-        }
+// Pays out unbonding stake whose cooldown is over, less the withdraw fee at this point. Once the
+// crop has no cooldown any more, nothing is left to wait for.
+pub fn release(
+    crop: &mut Crop,
+    plot: &mut Plot,
+    amount: u64,
+    current_timestamp: u64
+) -> Result<Withdrawal, ProgramError> {
+    if plot.unbonding_amount < amount {
+        return Err(FarmError::AmountIsTooLarge.into());
+    }
+    if crop.cooldown > 0 && current_timestamp < plot.unbonding_timestamp {
+        return Err(FarmError::StillUnbonding.into());
+    }
+    let withdraw_fee = calculate_withdraw_fee(crop, plot, current_timestamp)?;
+    let mut pool = pool(crop);
+    let withdrawal = accounting::release(&mut pool, amount, withdraw_fee).map_err(FarmError::from)?;
+    store_pool(crop, &pool);
+    plot.unbonding_amount -= amount;
+    Ok(withdrawal)
+}
+
+pub fn harvest(crop: &mut Crop, plot: &mut Plot, current_timestamp: u64) -> IntegerResult {
+    let (mut pool, mut position) = (pool(crop), position(plot));
+    let rewards = accounting::harvest(&mut pool, &mut position, current_timestamp)
+        .map_err(FarmError::from)?;
+    store_pool(crop, &pool);
+    store_position(plot, &position);
+    Ok(rewards)
+}
 
-        // Update the last timestamp where rewards were distributed
-        crop.previous_reward_timestamp = current_reward_timestamp;
+pub fn claim_fees(crop: &mut Crop, plot: &mut Plot) -> IntegerResult {
+    let (mut pool, mut position) = (pool(crop), position(plot));
+    let fees = accounting::claim_fees(&mut pool, &mut position)
+        .map_err(FarmError::from)?;
+    store_pool(crop, &pool);
+    store_position(plot, &position);
+    Ok(fees)
+}
+
+pub fn restake(
+    crop: &mut Crop,
+    plot: &mut Plot,
+    amount: u64,
+    current_timestamp: u64
+) -> ProgramResult {
+    // Receipts arriving from elsewhere count as a fresh deposit
+    if amount > plot.amount {
+        plot.deposit_timestamp = accounting::weighted_deposit_timestamp(
+            plot.amount,
+            plot.deposit_timestamp,
+            amount - plot.amount,
+            current_timestamp
+        ).map_err(FarmError::from)?;
     }
-    
+    let (mut pool, mut position) = (pool(crop), position(plot));
+    accounting::restake(&mut pool, &mut position, amount, current_timestamp)
+        .map_err(FarmError::from)?;
+    store_pool(crop, &pool);
+    store_position(plot, &position);
+    record_stake(crop, plot, current_timestamp);
     Ok(())
 }
 
+// Appends the amount as of the timestamp, dropping the oldest checkpoint once they are all used.
+// Changes within the same second share a checkpoint.
+pub fn record_checkpoint(checkpoints: &mut [Checkpoint], amount: u64, timestamp: u64) {
+    let used = checkpoints.iter().take_while(|checkpoint| checkpoint.timestamp != 0).count();
+    if used > 0 {
+        let latest = &mut checkpoints[used - 1];
+        if latest.timestamp == timestamp {
+            latest.amount = amount;
+            return;
+        }
+        if latest.amount == amount {
+            return;
+        }
+    }
+    let checkpoint = Checkpoint { timestamp, amount };
+    if used == checkpoints.len() {
+        checkpoints.rotate_left(1);
+        checkpoints[used - 1] = checkpoint;
+    } else {
+        checkpoints[used] = checkpoint;
+    }
+}
+
+// None before the oldest checkpoint, where the history has been dropped or never recorded
+pub fn amount_at(checkpoints: &[Checkpoint], timestamp: u64) -> Option<u64> {
+    checkpoints
+        .iter()
+        .take_while(|checkpoint| checkpoint.timestamp != 0)
+        .take_while(|checkpoint| checkpoint.timestamp <= timestamp)
+        .last()
+        .map(|checkpoint| checkpoint.amount)
+}
+
+pub fn record_stake(crop: &mut Crop, plot: &mut Plot, current_timestamp: u64) {
+    record_checkpoint(&mut crop.checkpoints, crop.total_deposited, current_timestamp);
+    record_checkpoint(&mut plot.checkpoints, plot.amount, current_timestamp);
+}
+
+pub fn apportion_rate(emission_rate: u64, alloc_points: u64, total_alloc_points: u64) -> IntegerResult {
+    Ok(accounting::apportion_rate(emission_rate, alloc_points, total_alloc_points).map_err(FarmError::from)?)
+}
+
+// Rewards capped at what is left of the crop's allocation
+pub fn payable_rewards(crop: &Crop, rewards: u64) -> u64 {
+    accounting::draw_budget(crop.reward_budget, crop.rewards_paid, rewards)
+}
+
+// Records the payout of rewards as far as the budget allows. Anything over the cap goes back to
+// the plot's unclaimed balance until the crop is topped up.
+pub fn draw_budget(crop: &mut Crop, plot: &mut Plot, rewards: u64) -> IntegerResult {
+    let payable = payable_rewards(crop, rewards);
+    plot.unclaimed = match plot.unclaimed.checked_add(rewards - payable) {
+        Some(x) => x,
+        None => return Err(FarmError::NumericalOverflowError.into()),
+    };
+    crop.rewards_paid = match crop.rewards_paid.checked_add(payable) {
+        Some(x) => x,
+        None => return Err(FarmError::NumericalOverflowError.into()),
+    };
+    Ok(payable)
+}
+
+pub fn credit_referral(referral: &mut Referral, amount: u64) -> ProgramResult {
+    referral.unclaimed = match referral.unclaimed.checked_add(amount) {
+        Some(x) => x,
+        None => return Err(FarmError::NumericalOverflowError.into()),
+    };
+    referral.total_earned = match referral.total_earned.checked_add(amount) {
+        Some(x) => x,
+        None => return Err(FarmError::NumericalOverflowError.into()),
+    };
+    Ok(())
+}
+
+pub fn load_referral<'info>(
+    info: &AccountInfo<'info>,
+    crop: &Pubkey,
+    referrer: &Pubkey
+) -> Result<Account<'info, Referral>, ProgramError> {
+    let referral = Account::<Referral>::try_from(info)?;
+    if referral.crop != *crop || referral.referrer != *referrer {
+        return Err(FarmError::InvalidReferralAccount.into());
+    }
+    Ok(referral)
+}
+
 pub fn assert_non_zero<T: Zero>(amount: T) -> ProgramResult {
     if amount.is_zero() {
         return Err(FarmError::AmountIsZero.into());
@@ -112,9 +395,332 @@ pub fn assert_enough_to_uproot(plot: &Plot, amount: u64) -> ProgramResult {
     Ok(())
 }
 
-pub fn assert_valid_fee(proposed_fee: u64) -> ProgramResult {
-    if proposed_fee > MAX_FEE {
+pub fn assert_sufficient_budget(crop: &Crop, amount: u64) -> ProgramResult {
+    if crop.reward_budget.saturating_sub(crop.rewards_paid) < amount {
+        return Err(FarmError::InsufficientBudget.into());
+    }
+    Ok(())
+}
+
+pub fn assert_fee_destination(manager: &Manager, token_account: &TokenAccount) -> ProgramResult {
+    if token_account.owner != manager.fee_recipient() {
+        return Err(FarmError::InvalidFeeDestination.into());
+    }
+    Ok(())
+}
+
+pub fn assert_collection_due(manager: &Manager, crop: &Crop, current_timestamp: u64) -> ProgramResult {
+    match crop.previous_collect_timestamp.checked_add(manager.collect_interval) {
+        Some(x) if manager.collect_interval > 0 && current_timestamp >= x => Ok(()),
+        _ => Err(FarmError::CollectionNotDue.into()),
+    }
+}
+
+// Plots do not record who they belong to, so the address has to match the farmer's plot seeds
+pub fn assert_plot_address(
+    address: &Pubkey,
+    plot: &Plot,
+    crop: &Crop,
+    farmer: &Pubkey,
+    program_id: &Pubkey
+) -> ProgramResult {
+    let expected = Pubkey::create_program_address(
+        &[b"plot".as_ref(), crop.manager.as_ref(), farmer.as_ref(), &crop.id.to_le_bytes(), &[plot.bump]],
+        program_id
+    );
+    match expected {
+        Ok(expected) if expected == *address => Ok(()),
+        _ => Err(FarmError::InvalidHarvestAccounts.into()),
+    }
+}
+
+// The farmer can always act on their own plot, a keeper only within their scope
+pub fn assert_keeper(plot: &Plot, farmer: &Pubkey, signer: &Pubkey, scope: KeeperScope) -> ProgramResult {
+    if signer == farmer {
+        return Ok(());
+    }
+    if plot.keeper == Pubkey::default() || plot.keeper != *signer || !plot.keeper_scope.allows(scope) {
+        return Err(FarmError::NotKeeper.into());
+    }
+    Ok(())
+}
+
+pub fn assert_compoundable(crop: &Crop) -> ProgramResult {
+    if crop.reward_mint != crop.deposit_mint {
+        return Err(FarmError::CannotCompound.into());
+    }
+    Ok(())
+}
+
+pub fn assert_no_receipts(crop: &Crop) -> ProgramResult {
+    if crop.receipt_mint != Pubkey::default() {
+        return Err(FarmError::CropIssuesReceipts.into());
+    }
+    Ok(())
+}
+
+pub fn assert_cooldown(crop: &Crop) -> ProgramResult {
+    if crop.cooldown == 0 {
+        return Err(FarmError::NoCooldown.into());
+    }
+    Ok(())
+}
+
+pub fn assert_no_cooldown(crop: &Crop) -> ProgramResult {
+    if crop.cooldown > 0 {
+        return Err(FarmError::CropHasCooldown.into());
+    }
+    Ok(())
+}
+
+pub fn assert_cooldown_compatible(cooldown: u64, receipt_mint: &Pubkey) -> ProgramResult {
+    if cooldown > 0 && *receipt_mint != Pubkey::default() {
+        return Err(FarmError::CooldownWithReceipts.into());
+    }
+    Ok(())
+}
+
+pub fn assert_not_paused(crop: &Crop) -> ProgramResult {
+    if crop.paused {
+        return Err(FarmError::CropIsPaused.into());
+    }
+    Ok(())
+}
+
+pub fn assert_crop_is_empty(crop: &Crop) -> ProgramResult {
+    if crop.total_deposited > 0 {
+        return Err(FarmError::CropNotEmpty.into());
+    }
+    Ok(())
+}
+
+pub fn assert_receipts_enabled(crop: &Crop) -> ProgramResult {
+    if crop.receipt_mint == Pubkey::default() {
+        return Err(FarmError::ReceiptsNotEnabled.into());
+    }
+    Ok(())
+}
+
+// Receipts only ever live in the farmer's associated token account for the receipt mint, so
+// nobody can point an instruction at some other account holding a different balance
+pub fn assert_receipt_accounts(
+    crop: &Crop,
+    receipt_mint: &Pubkey,
+    receipt_token_account: &Pubkey,
+    farmer: &Pubkey
+) -> ProgramResult {
+    if *receipt_mint != crop.receipt_mint
+        || *receipt_token_account != get_associated_token_address(farmer, &crop.receipt_mint) {
+        return Err(FarmError::InvalidReceiptAccount.into());
+    }
+    Ok(())
+}
+
+pub fn assert_receipts_backed(crop: &Crop, receipt_supply: u64) -> ProgramResult {
+    if crop.total_deposited > receipt_supply {
+        return Err(FarmError::UnbackedReceipts.into());
+    }
+    Ok(())
+}
+
+pub fn assert_valid_referrer(referrer: &Pubkey, farmer: &Pubkey) -> ProgramResult {
+    if referrer == farmer || *referrer == Pubkey::default() {
+        return Err(FarmError::InvalidReferrer.into());
+    }
+    Ok(())
+}
+
+pub fn assert_valid_timelock_delay(timelock_delay: u64) -> ProgramResult {
+    if timelock_delay < MIN_TIMELOCK_DELAY {
+        return Err(FarmError::TimelockDelayTooShort.into());
+    }
+    Ok(())
+}
+
+pub fn assert_valid_delay(
+    current_timestamp: u64,
+    effective_timestamp: u64,
+    timelock_delay: u64
+) -> ProgramResult {
+    // Managers appointed before the minimum existed still wait at least that long
+    match current_timestamp.checked_add(cmp::max(timelock_delay, MIN_TIMELOCK_DELAY)) {
+        Some(x) if effective_timestamp >= x => Ok(()),
+        _ => Err(FarmError::TimelockTooShort.into()),
+    }
+}
+
+// Drops whatever update was queued, whether it was applied or pruned
+pub fn clear_pending_update(crop: &mut Crop) {
+    crop.pending_params = CropParams::default();
+    crop.pending_penalty = Penalty::default();
+    crop.pending_fee_share = 0;
+    crop.pending_cooldown = 0;
+    crop.pending_timestamp = 0;
+}
+
+pub fn assert_pending_update(crop: &Crop) -> ProgramResult {
+    if crop.pending_timestamp == 0 {
+        return Err(FarmError::NoPendingUpdate.into());
+    }
+    Ok(())
+}
+
+pub fn assert_valid_fee(proposed_fee: u64, max_fee: u64) -> ProgramResult {
+    if proposed_fee > max_fee {
         return Err(FarmError::InvalidFee.into());
     }
     Ok(())
-}
\ No newline at end of file
+}
+
+// Lowering the stakers' share of fees raises what the owner takes, so it is bounded the same way
+// as a fee increase
+pub fn assert_valid_fee_share(
+    current_fee_share: u64,
+    proposed_fee_share: u64,
+    total_deposited: u64
+) -> ProgramResult {
+    assert_valid_fee(proposed_fee_share, MAX_FEE)?;
+    assert_valid_fee_increase(MAX_FEE - current_fee_share, MAX_FEE - proposed_fee_share, total_deposited)
+}
+
+pub fn assert_valid_fee_increase(
+    current_fee: u64,
+    proposed_fee: u64,
+    total_deposited: u64
+) -> ProgramResult {
+    if total_deposited > 0 && proposed_fee > current_fee.saturating_add(MAX_FEE_INCREASE) {
+        return Err(FarmError::FeeIncreaseTooLarge.into());
+    }
+    Ok(())
+}
+
+// The fee falls linearly between the ends of either penalty period and is flat outside them, so
+// the largest increase for a deposit of any age is found at one of those ages
+pub fn assert_valid_withdraw_fee_increase(
+    current_fee: u64,
+    current_penalty: &Penalty,
+    proposed_fee: u64,
+    proposed_penalty: &Penalty,
+    total_deposited: u64
+) -> ProgramResult {
+    for age in &[0, current_penalty.period, proposed_penalty.period, u64::MAX] {
+        assert_valid_fee_increase(
+            withdraw_fee_at_age(current_fee, current_penalty, *age)?,
+            withdraw_fee_at_age(proposed_fee, proposed_penalty, *age)?,
+            total_deposited
+        )?;
+    }
+    Ok(())
+}
+
+pub fn assert_not_apportioned(manager: &Manager, reward_rate: u64) -> ProgramResult {
+    if manager.is_apportioned() && reward_rate > 0 {
+        return Err(FarmError::RateIsApportioned.into());
+    }
+    Ok(())
+}
+
+pub fn assert_valid_metadata(name: &str, uri: &str, tags: &[String]) -> ProgramResult {
+    if name.len() > Metadata::MAX_NAME_LENGTH
+        || uri.len() > Metadata::MAX_URI_LENGTH
+        || tags.len() > Metadata::MAX_TAGS
+        || tags.iter().any(|tag| tag.len() > Metadata::MAX_TAG_LENGTH)
+    {
+        return Err(FarmError::MetadataTooLong.into());
+    }
+    Ok(())
+}
+
+pub fn assert_account_type<T: Discriminator>(info: &AccountInfo, program_id: &Pubkey) -> ProgramResult {
+    let data = info.try_borrow_data()?;
+    if info.owner != program_id || data.len() < 8 || data[..8] != T::discriminator() {
+        return Err(FarmError::InvalidAccountType.into());
+    }
+    Ok(())
+}
+
+pub fn assert_outdated(version: u8, current_version: u8) -> ProgramResult {
+    if version >= current_version {
+        return Err(FarmError::AccountIsCurrent.into());
+    }
+    Ok(())
+}
+
+// Creates a program account at a PDA the way init does. Lamports already sent to the address are
+// topped up rather than refused, so nobody can block it by funding it first.
+#[allow(clippy::too_many_arguments)]
+pub fn create_pda_account<'info>(
+    info: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    rent: &Rent,
+    space: usize,
+    seeds: &[&[u8]],
+    program_id: &Pubkey
+) -> ProgramResult {
+    let lamports = rent.minimum_balance(space);
+    if info.lamports() == 0 {
+        return invoke_signed(
+            &system_instruction::create_account(payer.key, info.key, lamports, space as u64, program_id),
+            &[payer.clone(), info.clone(), system_program.clone()],
+            &[seeds]
+        );
+    }
+    let shortfall = lamports.saturating_sub(info.lamports());
+    if shortfall > 0 {
+        invoke(
+            &system_instruction::transfer(payer.key, info.key, shortfall),
+            &[payer.clone(), info.clone(), system_program.clone()]
+        )?;
+    }
+    invoke_signed(
+        &system_instruction::allocate(info.key, space as u64),
+        &[info.clone(), system_program.clone()],
+        &[seeds]
+    )?;
+    invoke_signed(
+        &system_instruction::assign(info.key, program_id),
+        &[info.clone(), system_program.clone()],
+        &[seeds]
+    )
+}
+
+// Decodes an account still on its version 0 layout, recognised by its exact size. Anything else
+// is left to the current layout.
+pub fn read_version_zero<T: AnchorDeserialize>(info: &AccountInfo, space: usize) -> Result<Option<T>, ProgramError> {
+    if info.data_len() != 8 + space {
+        return Ok(None);
+    }
+    let data = info.try_borrow_data()?;
+    Ok(Some(T::deserialize(&mut &data[8..])?))
+}
+
+// Zeroes everything after the discriminator so a version 0 account can be rewritten field by field
+pub fn clear_account(info: &AccountInfo) -> ProgramResult {
+    let mut data = info.try_borrow_mut_data()?;
+    data[8..].fill(0);
+    Ok(())
+}
+
+// Grows an account written with an older, smaller layout to the current size, with the payer
+// covering the extra rent. The new bytes are zeroed, so the version reads as 0 and reserved
+// fields read as empty.
+pub fn grow_account<'info>(
+    info: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    rent: &Rent,
+    space: usize
+) -> ProgramResult {
+    if info.data_len() >= space {
+        return Ok(());
+    }
+    let lamports = rent.minimum_balance(space).saturating_sub(info.lamports());
+    if lamports > 0 {
+        invoke(
+            &system_instruction::transfer(payer.key, info.key, lamports),
+            &[payer.clone(), info.clone(), system_program.clone()]
+        )?;
+    }
+    info.realloc(space, true)
+}
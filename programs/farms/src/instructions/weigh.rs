@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{Crop, Plot};
+use crate::events::StakeAt;
+use crate::instructions::utils;
+
+// Read only, meant to be simulated by clients which then read the emitted event
+#[derive(Accounts)]
+pub struct Weigh<'info> {
+
+    #[account(
+        seeds = [b"crop".as_ref(), crop.manager.as_ref(), &crop.id.to_le_bytes()],
+        bump = crop.bump
+    )]
+    pub crop: Box<Account<'info, Crop>>,
+
+    #[account(
+        seeds = [b"plot".as_ref(), crop.manager.as_ref(), farmer.key().as_ref(), &crop.id.to_le_bytes()],
+        bump = plot.bump
+    )]
+    pub plot: Account<'info, Plot>,
+
+    pub farmer: AccountInfo<'info>,
+
+}
+
+pub fn handler(ctx: Context<Weigh>, timestamp: u64) -> ProgramResult {
+    emit!(StakeAt {
+        crop: ctx.accounts.crop.key(),
+        plot: ctx.accounts.plot.key(),
+        timestamp,
+        amount: utils::amount_at(&ctx.accounts.plot.checkpoints, timestamp),
+        total_deposited: utils::amount_at(&ctx.accounts.crop.checkpoints, timestamp),
+    });
+
+    Ok(())
+}
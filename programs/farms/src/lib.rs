@@ -1,10 +1,12 @@
 use anchor_lang::prelude::*;
 
 pub mod errors;
+pub mod events;
 pub mod state;
 pub mod instructions;
 
 use instructions::*;
+use state::{Penalty, Role, KeeperScope, MetadataParams};
 
 declare_id!("H4USCP7cY9Rpsu6j3N6uLQ7tF1aNN9rLF9WfvjFGDfLe");
 
@@ -13,8 +15,13 @@ pub mod farms {
     use super::*;
 
     // Create manager
-    pub fn appoint(ctx: Context<Appoint>, rewarder_bump: u8) -> ProgramResult {
-        instructions::appoint::handler(ctx, rewarder_bump)
+    pub fn appoint(
+        ctx: Context<Appoint>,
+        rewarder_bump: u8,
+        timelock_delay: u64,
+        max_fee: u64
+    ) -> ProgramResult {
+        instructions::appoint::handler(ctx, rewarder_bump, timelock_delay, max_fee)
     }
 
     // Transfer ownership
@@ -22,60 +29,265 @@ pub mod farms {
         instructions::entrust::handler(ctx, trustee)
     }
 
+    // Lower the fee ceiling
+    pub fn hedge(ctx: Context<Hedge>, max_fee: u64) -> ProgramResult {
+        instructions::hedge::handler(ctx, max_fee)
+    }
+
+    // Grant a role
+    pub fn grant(ctx: Context<Grant>, role: Role, grantee: Pubkey) -> ProgramResult {
+        instructions::grant::handler(ctx, role, grantee)
+    }
+
+    // Revoke a role
+    pub fn revoke(ctx: Context<Revoke>, role: Role) -> ProgramResult {
+        instructions::revoke::handler(ctx, role)
+    }
+
     // Create farm
-    pub fn cultivate(
-        ctx: Context<Cultivate>, 
+    #[allow(clippy::too_many_arguments)]
+    pub fn cultivate<'info>(
+        ctx: Context<'_, '_, '_, 'info, Cultivate<'info>>,
         seed: u8,
         deposit_fee: u64,
         withdraw_fee: u64,
+        referral_fee: u64,
         end_timestamp: u64,
         reward_rate: u64,
+        penalty: Penalty,
+        fee_share: u64,
+        cooldown: u64,
+        metadata: Option<MetadataParams>,
     ) -> ProgramResult {
         instructions::cultivate::handler(
             ctx,
             deposit_fee,
             withdraw_fee,
+            referral_fee,
             end_timestamp,
             reward_rate,
+            penalty,
+            fee_share,
+            cooldown,
+            metadata,
             seed
         )
     }
 
-    // Update farm
+    // Queue farm update
+    #[allow(clippy::too_many_arguments)]
     pub fn recultivate(
         ctx: Context<Recultivate>, 
         deposit_fee: u64,
         withdraw_fee: u64,
+        referral_fee: u64,
         end_timestamp: u64,
-        reward_rate: u64
+        reward_rate: u64,
+        penalty: Penalty,
+        fee_share: u64,
+        cooldown: u64,
+        effective_timestamp: u64
     ) -> ProgramResult {
         instructions::recultivate::handler(
             ctx,
             deposit_fee,
             withdraw_fee,
+            referral_fee,
             end_timestamp,
-            reward_rate
+            reward_rate,
+            penalty,
+            fee_share,
+            cooldown,
+            effective_timestamp
         )
     }
 
+    // Apply queued farm update
+    pub fn ripen(ctx: Context<Ripen>) -> ProgramResult {
+        instructions::ripen::handler(ctx)
+    }
+
+    // Cancel queued farm update
+    pub fn prune(ctx: Context<Prune>) -> ProgramResult {
+        instructions::prune::handler(ctx)
+    }
+
+    // Pause or resume farm
+    pub fn fallow(ctx: Context<Fallow>, paused: bool) -> ProgramResult {
+        instructions::fallow::handler(ctx, paused)
+    }
+
     // Create user account
+    #[allow(unused_variables)]
     pub fn till(ctx: Context<Till>, seed: u8, crop_id: u64) -> ProgramResult {
         instructions::till::handler(ctx, seed)
     }
 
     // Deposit
-    pub fn sow(ctx: Context<Sow>, amount: u64) -> ProgramResult {
-        instructions::sow::handler(ctx, amount)
+    pub fn sow<'info>(ctx: Context<'_, '_, '_, 'info, Sow<'info>>, amount: u64, referrer: Option<Pubkey>) -> ProgramResult {
+        instructions::sow::handler(ctx, amount, referrer)
     }
 
     // Withdraw
-    pub fn uproot(ctx: Context<Uproot>, amount: u64) -> ProgramResult {
+    pub fn uproot<'info>(ctx: Context<'_, '_, '_, 'info, Uproot<'info>>, amount: u64) -> ProgramResult {
         instructions::uproot::handler(ctx, amount)
     }
 
-    // Collect fees 
-    pub fn collect(ctx: Context<Collect>) -> ProgramResult {
-        instructions::collect::handler(ctx)
+    // Claim rewards from several crops at once
+    pub fn harvest_many<'info>(ctx: Context<'_, '_, '_, 'info, HarvestMany<'info>>) -> ProgramResult {
+        instructions::harvest_many::handler(ctx)
+    }
+
+    // Let a keeper harvest, or also compound, on the farmer's behalf
+    pub fn hire(ctx: Context<Hire>, keeper: Pubkey, scope: KeeperScope) -> ProgramResult {
+        instructions::hire::handler(ctx, keeper, scope)
+    }
+
+    // Remove the plot's keeper
+    pub fn dismiss(ctx: Context<Dismiss>) -> ProgramResult {
+        instructions::dismiss::handler(ctx)
+    }
+
+    // Harvest to the farmer, as the farmer or their keeper
+    pub fn glean(ctx: Context<Glean>) -> ProgramResult {
+        instructions::glean::handler(ctx)
+    }
+
+    // Stake harvested rewards back into the farm, as the farmer or their keeper
+    pub fn compound(ctx: Context<Compound>) -> ProgramResult {
+        instructions::compound::handler(ctx)
+    }
+
+    // Move stake to another farm
+    pub fn migrate_stake(ctx: Context<MigrateStake>, amount: u64) -> ProgramResult {
+        instructions::migrate_stake::handler(ctx, amount)
+    }
+
+    // Waive fees for stake moving between two farms
+    pub fn chart(ctx: Context<Chart>, seed: u8) -> ProgramResult {
+        instructions::chart::handler(ctx, seed)
+    }
+
+    // Remove a fee-free route
+    pub fn unchart(ctx: Context<Unchart>) -> ProgramResult {
+        instructions::unchart::handler(ctx)
+    }
+
+    // Start the cooldown on part of a deposit
+    pub fn request_uproot(ctx: Context<RequestUproot>, amount: u64) -> ProgramResult {
+        instructions::request_uproot::handler(ctx, amount)
+    }
+
+    // Put stake that is cooling down back in the farm
+    pub fn cancel_uproot(ctx: Context<CancelUproot>) -> ProgramResult {
+        instructions::cancel_uproot::handler(ctx)
+    }
+
+    // Collect fees, all of them when no amount is given. Batch several crops in one transaction.
+    pub fn collect(ctx: Context<Collect>, amount: Option<u64>) -> ProgramResult {
+        instructions::collect::handler(ctx, amount)
+    }
+
+    // Collect due fees to the fee recipient
+    pub fn reap(ctx: Context<Reap>) -> ProgramResult {
+        instructions::reap::handler(ctx)
+    }
+
+    // Set where fees go and how often anyone can collect them
+    pub fn consign(ctx: Context<Consign>, fee_recipient: Pubkey, collect_interval: u64) -> ProgramResult {
+        instructions::consign::handler(ctx, fee_recipient, collect_interval)
+    }
+
+    // View pending rewards
+    pub fn appraise(ctx: Context<Appraise>) -> ProgramResult {
+        instructions::appraise::handler(ctx)
+    }
+
+    // View staked amounts as of a past timestamp
+    pub fn weigh(ctx: Context<Weigh>, timestamp: u64) -> ProgramResult {
+        instructions::weigh::handler(ctx, timestamp)
+    }
+
+    // Accrue a farm's rewards up to now, anyone can call it
+    pub fn tend(ctx: Context<Tend>) -> ProgramResult {
+        instructions::tend::handler(ctx)
+    }
+
+    // Issue receipt tokens for deposits
+    #[allow(unused_variables)]
+    pub fn certify(ctx: Context<Certify>, seed: u8) -> ProgramResult {
+        instructions::certify::handler(ctx)
+    }
+
+    // Settle a plot against its farmer's receipt balance, anyone can bring it down
+    pub fn reckon(ctx: Context<Reckon>) -> ProgramResult {
+        instructions::reckon::handler(ctx)
+    }
+
+    // Create referral account
+    pub fn enlist(ctx: Context<Enlist>, seed: u8) -> ProgramResult {
+        instructions::enlist::handler(ctx, seed)
+    }
+
+    // Claim referral kickbacks
+    pub fn redeem(ctx: Context<Redeem>) -> ProgramResult {
+        instructions::redeem::handler(ctx)
+    }
+
+    // Set farm name, uri and tags
+    pub fn label(
+        ctx: Context<Label>,
+        seed: u8,
+        name: String,
+        uri: String,
+        tags: Vec<String>
+    ) -> ProgramResult {
+        instructions::label::handler(ctx, seed, name, uri, tags)
+    }
+
+    // Queue a split of emissions across farms by allocation points
+    pub fn apportion<'info>(
+        ctx: Context<'_, '_, '_, 'info, Apportion<'info>>,
+        emission_rate: u64,
+        alloc_points: Vec<u64>,
+        effective_timestamp: u64
+    ) -> ProgramResult {
+        instructions::apportion::handler(ctx, emission_rate, alloc_points, effective_timestamp)
+    }
+
+    // Switch farms over to the queued split once it is effective
+    pub fn rotate<'info>(ctx: Context<'_, '_, '_, 'info, Rotate<'info>>) -> ProgramResult {
+        instructions::rotate::handler(ctx)
+    }
+
+    // Fund farm rewards
+    pub fn fertilize(ctx: Context<Fertilize>, amount: u64) -> ProgramResult {
+        instructions::fertilize::handler(ctx, amount)
+    }
+
+    // Fund a reward period of a given length, rolling in what the current one has left
+    pub fn irrigate(ctx: Context<Irrigate>, amount: u64, duration: u64) -> ProgramResult {
+        instructions::irrigate::handler(ctx, amount, duration)
+    }
+
+    // Move reward budget between farms
+    pub fn reallot(ctx: Context<Reallot>, amount: u64) -> ProgramResult {
+        instructions::reallot::handler(ctx, amount)
+    }
+
+    // Upgrade a manager to the current layout
+    pub fn migrate_manager(ctx: Context<MigrateManager>) -> ProgramResult {
+        instructions::migrate_manager::handler(ctx)
+    }
+
+    // Upgrade a farm to the current layout
+    pub fn migrate_crop(ctx: Context<MigrateCrop>) -> ProgramResult {
+        instructions::migrate_crop::handler(ctx)
+    }
+
+    // Upgrade a user account to the current layout
+    pub fn migrate_plot(ctx: Context<MigratePlot>) -> ProgramResult {
+        instructions::migrate_plot::handler(ctx)
     }
 
 }
@@ -1,23 +1,105 @@
 use anchor_lang::prelude::*;
-use borsh::{BorshDeserialize, BorshSerialize};
 
 #[account]
 pub struct Manager {
 
     pub owner: Pubkey,
 
+    // Roles default to the pubkey default, in which case only the owner holds them
+    pub operator: Pubkey,
+
+    pub fee_collector: Pubkey,
+
+    pub pauser: Pubkey,
+
     pub crops: u64,
 
+    // Minimum number of seconds between queueing a crop update and applying it
+    pub timelock_delay: u64,
+
+    // Ceiling on every fee of every crop, set when the manager is appointed. The owner can only
+    // lower it.
+    pub max_fee: u64,
+
     pub rewarder_bump: u8,
 
+    // Accounts written before versioning read as version 0
+    pub version: u8,
+
+    // Split across crops by allocation points. While it is set, crop reward rates follow their
+    // points and queued updates leave them alone.
+    pub emission_rate: u64,
+
+    pub total_alloc_points: u64,
+
+    // Owner of every account fees are collected to. Default pubkey means the owner.
+    pub fee_recipient: Pubkey,
+
+    // Seconds between collections anyone can trigger with reap, zero turns them off
+    pub collect_interval: u64,
+
+    // Crops rotated into the queued apportionment so far, always the lowest ids
+    pub rotated_crops: u64,
+
+    // Queued by apportion, a crop at a time. Rotate moves every crop to its new rate once every
+    // crop has its points and the timestamp has passed, then clears these.
+    pub pending_emission_rate: u64,
+
+    pub pending_total_alloc_points: u64,
+
+    // Crops given points so far, always the lowest ids
+    pub apportioned_crops: u64,
+
+    pub pending_apportion_timestamp: u64,
+
 }
 
 impl Manager {
+    pub const VERSION: u8 = 2;
+
     pub fn space() -> usize {
-        1 * 8 + 1 * 32 + 1
+        11 * 8 + 5 * 32 + 2
+    }
+
+    // Rates follow points as soon as the first crop is rotated in
+    pub fn is_apportioned(&self) -> bool {
+        self.emission_rate > 0 || self.rotated_crops > 0
+    }
+
+    pub fn is_apportionment_ready(&self, current_timestamp: u64) -> bool {
+        self.pending_apportion_timestamp > 0
+            && self.apportioned_crops == self.crops
+            && current_timestamp >= self.pending_apportion_timestamp
+    }
+
+    pub fn fee_recipient(&self) -> Pubkey {
+        if self.fee_recipient == Pubkey::default() {
+            self.owner
+        } else {
+            self.fee_recipient
+        }
+    }
+
+    pub fn is_operator(&self, key: &Pubkey) -> bool {
+        *key == self.owner || *key == self.operator
+    }
+
+    pub fn is_fee_collector(&self, key: &Pubkey) -> bool {
+        *key == self.owner || *key == self.fee_collector
+    }
+
+    pub fn is_pauser(&self, key: &Pubkey) -> bool {
+        *key == self.owner || *key == self.pauser
     }
 }
 
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq)]
+pub enum Role {
+    Operator,
+    FeeCollector,
+    Pauser,
+}
+
 #[account]
 pub struct Crop {
 
@@ -31,12 +113,18 @@ pub struct Crop {
 
     pub deposit_mint: Pubkey,
 
+    // Default pubkey when the crop does not issue receipts
+    pub receipt_mint: Pubkey,
+
     pub end_timestamp: u64,
 
     pub deposit_fee: u64,
 
     pub withdraw_fee: u64,
 
+    // Share of harvested rewards kicked back to the farmer's referrer
+    pub referral_fee: u64,
+
     pub total_deposited: u64,
 
     pub fees: u64,
@@ -47,17 +135,115 @@ pub struct Crop {
 
     pub previous_reward_timestamp: u64,
 
+    // Update queued by recultivate, only meaningful while the pending timestamp is set
+    pub pending_params: CropParams,
+
+    pub pending_timestamp: u64,
+
     pub paused: bool,
 
     pub bump: u8,
 
-    pub id: u64
+    pub id: u64,
+
+    pub version: u8,
+
+    // Share of the manager's emission rate
+    pub alloc_points: u64,
+
+    // Every crop pays out of its own budget, so no crop can spend what another crop sharing its
+    // reward treasury was funded with
+    pub reward_budget: u64,
+
+    // Referral kickbacks count once they are credited
+    pub rewards_paid: u64,
+
+    // Set while apportion has queued points the crop has yet to ripen into
+    pub pending_apportion: bool,
+
+    // Room for new fields, zero until a migration gives them a meaning
+    pub reserved: [u8; 7],
+
+    // Charged instead of the withdraw fee when set, on deposits younger than the penalty period
+    pub penalty: Penalty,
+
+    // Queued by recultivate alongside the pending params
+    pub pending_penalty: Penalty,
+
+    // Part of every fee paid to the farmers staked when it is charged, the rest is collected
+    pub fee_share: u64,
+
+    pub pending_fee_share: u64,
+
+    pub fees_per_share: u64,
+
+    // Shared fees held in the deposit treasury until farmers claim them
+    pub staker_fees: u64,
+
+    pub previous_collect_timestamp: u64,
+
+    // Seconds requested withdrawals wait before they can be uprooted, zero for instant withdrawals
+    pub cooldown: u64,
+
+    pub pending_cooldown: u64,
+
+    // Queued by apportion alongside the pending apportion flag
+    pub pending_alloc_points: u64,
+
+    // Total deposited after each of the latest changes, oldest first
+    pub checkpoints: [Checkpoint; 32],
 
 }
 
 impl Crop {
+    pub const VERSION: u8 = 2;
+    pub const CHECKPOINTS: usize = 32;
+
+    pub fn space() -> usize {
+        6 * 32 + 22 * 8 + 4 + 7 + CropParams::space() + 2 * Penalty::space()
+            + Crop::CHECKPOINTS * Checkpoint::space()
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct CropParams {
+
+    pub deposit_fee: u64,
+
+    pub withdraw_fee: u64,
+
+    pub referral_fee: u64,
+
+    pub end_timestamp: u64,
+
+    pub reward_rate: u64,
+
+}
+
+impl CropParams {
+    pub fn space() -> usize {
+        5 * 8
+    }
+}
+
+// Starts at max right after a deposit and falls linearly to zero over the period
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct Penalty {
+
+    pub max: u64,
+
+    pub period: u64,
+
+}
+
+impl Penalty {
     pub fn space() -> usize {
-        5 * 32 + 9 * 8 + 2 * 1
+        2 * 8
+    }
+
+    // A crop with a penalty charges it in place of the flat withdraw fee
+    pub fn is_set(&self) -> bool {
+        self.max > 0 && self.period > 0
     }
 }
 
@@ -68,12 +254,240 @@ pub struct Plot {
 
     pub debt: u64,
 
+    pub unclaimed: u64,
+
+    // Default pubkey when the farmer was not referred
+    pub referrer: Pubkey,
+
     pub bump: u8,
 
+    pub version: u8,
+
+    // Average time the stake was deposited at, weighted by amount. Zero for plots that predate it,
+    // which never pay a penalty.
+    pub deposit_timestamp: u64,
+
+    pub fee_debt: u64,
+
+    // Shared fees owed to the farmer, paid in the deposit mint
+    pub unclaimed_fees: u64,
+
+    // Stake requested for withdrawal, no longer earning, and when it can be uprooted
+    pub unbonding_amount: u64,
+
+    pub unbonding_timestamp: u64,
+
+    pub reserved: [u64; 3],
+
+    // Staked amount after each of the latest changes, oldest first
+    pub checkpoints: [Checkpoint; 16],
+
+    // Can act on the plot for the farmer within the scope, default pubkey when there is none.
+    // Rewards still only go to the farmer.
+    pub keeper: Pubkey,
+
+    pub keeper_scope: KeeperScope,
+
 }
 
 impl Plot {
+    pub const VERSION: u8 = 3;
+    pub const CHECKPOINTS: usize = 16;
+
+    pub fn space() -> usize {
+        8 * 8 + 2 * 32 + 3 + 3 * 8 + Plot::CHECKPOINTS * Checkpoint::space()
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq)]
+pub enum KeeperScope {
+    Harvest,
+    // Harvest as well as compound
+    Compound,
+}
+
+impl KeeperScope {
+    pub fn allows(&self, scope: KeeperScope) -> bool {
+        *self == KeeperScope::Compound || scope == KeeperScope::Harvest
+    }
+}
+
+// An amount staked from the timestamp on. Unused checkpoints have a zero timestamp.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct Checkpoint {
+
+    pub timestamp: u64,
+
+    pub amount: u64,
+
+}
+
+impl Checkpoint {
+    pub fn space() -> usize {
+        2 * 8
+    }
+}
+
+#[account]
+pub struct Referral {
+
+    pub crop: Pubkey,
+
+    pub referrer: Pubkey,
+
+    pub referred: u64,
+
+    pub unclaimed: u64,
+
+    pub total_earned: u64,
+
+    pub bump: u8,
+
+}
+
+impl Referral {
+    pub fn space() -> usize {
+        2 * 32 + 3 * 8 + 1
+    }
+}
+
+// Marks stake moving from one crop to another as a fee-free migration
+#[account]
+pub struct Route {
+
+    pub from_crop: Pubkey,
+
+    pub to_crop: Pubkey,
+
+    pub bump: u8,
+
+}
+
+impl Route {
+    pub fn space() -> usize {
+        2 * 32 + 1
+    }
+}
+
+// Display information for a crop, kept out of the crop so it can grow without a migration
+#[account]
+pub struct Metadata {
+
+    pub crop: Pubkey,
+
+    pub name: String,
+
+    // Points to extended JSON metadata
+    pub uri: String,
+
+    pub tags: Vec<String>,
+
+    pub bump: u8,
+
+}
+
+impl Metadata {
+    pub const MAX_NAME_LENGTH: usize = 32;
+    pub const MAX_URI_LENGTH: usize = 200;
+    pub const MAX_TAGS: usize = 4;
+    pub const MAX_TAG_LENGTH: usize = 16;
+
+    // Strings and vectors are prefixed with their length
+    pub fn space() -> usize {
+        32 + (4 + Self::MAX_NAME_LENGTH)
+            + (4 + Self::MAX_URI_LENGTH)
+            + 4 + Self::MAX_TAGS * (4 + Self::MAX_TAG_LENGTH)
+            + 1
+    }
+}
+
+// Metadata given when the crop is cultivated
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct MetadataParams {
+
+    pub name: String,
+
+    pub uri: String,
+
+    pub tags: Vec<String>,
+
+}
+
+// Layouts written before accounts were versioned. Their fields sit in a different order, so
+// migrations decode them whole and rewrite them into the current layout.
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct ManagerV0 {
+
+    pub owner: Pubkey,
+
+    pub crops: u64,
+
+    pub rewarder_bump: u8,
+
+}
+
+impl ManagerV0 {
+    pub fn space() -> usize {
+        8 + 32 + 1
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct CropV0 {
+
+    pub manager: Pubkey,
+
+    pub reward_treasury: Pubkey,
+
+    pub reward_mint: Pubkey,
+
+    pub deposit_treasury: Pubkey,
+
+    pub deposit_mint: Pubkey,
+
+    pub end_timestamp: u64,
+
+    pub deposit_fee: u64,
+
+    pub withdraw_fee: u64,
+
+    pub total_deposited: u64,
+
+    pub fees: u64,
+
+    pub rewards_per_share: u64,
+
+    pub reward_rate: u64,
+
+    pub previous_reward_timestamp: u64,
+
+    pub paused: bool,
+
+    pub bump: u8,
+
+    pub id: u64,
+
+}
+
+impl CropV0 {
+    pub fn space() -> usize {
+        5 * 32 + 9 * 8 + 2
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct PlotV0 {
+
+    pub amount: u64,
+
+    pub debt: u64,
+
+    pub bump: u8,
+
+}
+
+impl PlotV0 {
     pub fn space() -> usize {
         2 * 8 + 1
     }
-}
\ No newline at end of file
+}
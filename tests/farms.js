@@ -1,8 +1,11 @@
 const anchor = require('@project-serum/anchor');
-const { PublicKey, SYSVAR_RENT_PUBKEY, SystemProgram, Keypair, Transaction } = anchor.web3;
+const { PublicKey, SYSVAR_RENT_PUBKEY, SYSVAR_CLOCK_PUBKEY, SystemProgram, Keypair, Transaction } = anchor.web3;
 const { Token, TOKEN_PROGRAM_ID, AccountLayout } = require('@solana/spl-token');
+const { assert } = require('chai');
 
 const DECIMALS = 9;
+// The shortest timelock delay the program accepts
+const TIMELOCK_DELAY = 86_400;
 const createMint = async (connection, payer, authority) => {
     return await Token.createMint(
         connection, payer, 
@@ -34,6 +37,10 @@ const createTokenAccount = async (connection, payer, mint, owner) => {
 	return keypair.publicKey;
 }
 
+const getClockTimestamp = async (connection) => {
+	const clock = await connection.getAccountInfo(SYSVAR_CLOCK_PUBKEY);
+	return Number(clock.data.readBigInt64LE(32));
+}
 
 describe('farms', () => {
 
@@ -72,6 +79,27 @@ describe('farms', () => {
 		);
 	};
 
+	const findReferral = async (crop, referrer) => {
+		return await PublicKey.findProgramAddress(
+			[Buffer.from("referral"), crop.toBuffer(), referrer.toBuffer()], 
+			program.programId
+		);
+	};
+
+	const findMetadata = async (crop) => {
+		return await PublicKey.findProgramAddress(
+			[Buffer.from("metadata"), crop.toBuffer()], 
+			program.programId
+		);
+	};
+
+	const findReceiptMint = async (crop) => {
+		return await PublicKey.findProgramAddress(
+			[Buffer.from("receipt"), crop.toBuffer()], 
+			program.programId
+		);
+	};
+
 	const managerA = Keypair.generate();
 
 	var depositMintA;
@@ -94,7 +122,7 @@ describe('farms', () => {
 	it('appoint manager', async () => {
 
 		const [rewarderPda, rewarderBump] = await findRewarderPDA(managerA.publicKey);
-		await program.rpc.appoint(rewarderBump, {
+		await program.rpc.appoint(rewarderBump, new anchor.BN(TIMELOCK_DELAY), new anchor.BN(100_000_000), {
 			accounts: {
 				manager: managerA.publicKey,
 				rewarderPda,
@@ -107,7 +135,7 @@ describe('farms', () => {
 
 	});
 
-	const cultivateCrop = async (manager, depositMint, rewardMint, id) => {
+	const cultivateCrop = async (manager, depositMint, rewardMint, id, metadata = null, remainingAccounts = []) => {
 		const [rewarderPda, ] = await findRewarderPDA(manager.publicKey);
 
 		const depositTreasuryKeypair = Keypair.generate();
@@ -115,6 +143,7 @@ describe('farms', () => {
 
 		const depositFee = new anchor.BN(0);
 		const withdrawFee = new anchor.BN(0);
+		const referralFee = new anchor.BN(0);
 		const endTimestamp = new anchor.BN(0);
 		const rewardRate = new anchor.BN(0);
 		const [crop, seed] = await findCrop(manager.publicKey, id);
@@ -123,8 +152,13 @@ describe('farms', () => {
 			seed,
 			depositFee, 
 			withdrawFee,
+			referralFee,
 			endTimestamp,
 			rewardRate,
+			{ max: new anchor.BN(0), period: new anchor.BN(0) },
+			new anchor.BN(0),
+			new anchor.BN(0),
+			metadata,
 			{
 				accounts: {
 					manager: manager.publicKey,
@@ -134,11 +168,12 @@ describe('farms', () => {
 					rewardTreasury: rewardTreasuryKeypair.publicKey,
 					depositMint: depositMint.publicKey,
 					rewardMint: rewardMint.publicKey,
-					owner: payer.publicKey,
+					operator: payer.publicKey,
 					tokenProgram: TOKEN_PROGRAM_ID,
 					systemProgram: SystemProgram.programId,
 					rent: SYSVAR_RENT_PUBKEY
 				},
+				remainingAccounts,
 				signers: [depositTreasuryKeypair, rewardTreasuryKeypair]
 			}
 		)
@@ -150,7 +185,7 @@ describe('farms', () => {
 		await cultivateCrop(managerA, depositMintC, rewardMintC, 2);
 	});
 
-	const tillPlot = async (manager, farmer, id) => {
+	const tillPlot = async (manager, farmer, id, signers = []) => {
 		const [plot, seed] = await findPlot(manager, farmer, id);
 		await program.rpc.till(seed, new anchor.BN(id), {
 			accounts: {
@@ -159,7 +194,8 @@ describe('farms', () => {
 				farmer,
 				systemProgram: SystemProgram.programId,
 				rent: SYSVAR_RENT_PUBKEY
-			}
+			},
+			signers
 		})
 	}
 
@@ -169,7 +205,7 @@ describe('farms', () => {
 		await tillPlot(managerA.publicKey, payer.publicKey, 2);
 	})
 
-	const sow = async (manager, farmer, amount, id) => {
+	const sow = async (manager, farmer, amount, id, remainingAccounts = [], referrer = null, signers = []) => {
 
 		const [crop, ] = await findCrop(manager, id);
 		const [plot, ] = await findPlot(manager, farmer, id);
@@ -180,7 +216,7 @@ describe('farms', () => {
 		let fromTokenAccount = await mint.createAccount(farmer);
 		await mint.mintTo(fromTokenAccount, payer.publicKey, [], amount);
 
-		await program.rpc.sow(new anchor.BN(amount), {
+		await program.rpc.sow(new anchor.BN(amount), referrer, {
 			accounts: {
 				crop,
 				plot,
@@ -188,12 +224,14 @@ describe('farms', () => {
 				fromTokenAccount,
 				farmer,
 				tokenProgram: TOKEN_PROGRAM_ID
-			}
+			},
+			remainingAccounts,
+			signers
 		});
 
 	}
 
-	const uproot = async (manager, farmer, amount, id) => {
+	const uproot = async (manager, farmer, amount, id, remainingAccounts = []) => {
 
 		const [rewarderPda, ] = await findRewarderPDA(manager);
 		const [crop, ] = await findCrop(manager, id);
@@ -221,16 +259,228 @@ describe('farms', () => {
 				rewardTokenAccount,
 				farmer,
 				tokenProgram: TOKEN_PROGRAM_ID
-			}
+			},
+			remainingAccounts
 		});
 
 	}
 
+	const recultivateCrop = async (manager, id, rewardRate, effectiveTimestamp) => {
+		const [crop, ] = await findCrop(manager, id);
+		const zero = new anchor.BN(0);
+		await program.rpc.recultivate(
+			zero,
+			zero,
+			zero,
+			zero,
+			new anchor.BN(rewardRate),
+			{ max: zero, period: zero },
+			zero,
+			zero,
+			new anchor.BN(effectiveTimestamp),
+			{
+				accounts: {
+					manager,
+					crop,
+					operator: payer.publicKey
+				}
+			}
+		);
+	}
+
+	// The local validator cannot warp its clock past the timelock delay, so applying an update
+	// once it is effective is covered by the program tests
+	it("queues crop updates", async () => {
+		const [crop, ] = await findCrop(managerA.publicKey, 2);
+
+		try {
+			await recultivateCrop(managerA.publicKey, 2, 5, (await getClockTimestamp(connection)) + 3600);
+			assert.fail("queued an update inside the timelock delay");
+		} catch (err) {
+			assert.equal(err.msg, "Updates must take effect after the timelock delay");
+		}
+
+		const later = (await getClockTimestamp(connection)) + TIMELOCK_DELAY + 60;
+		await recultivateCrop(managerA.publicKey, 2, 5, later);
+		let cropData = await program.account.crop.fetch(crop);
+		assert.equal(cropData.pendingParams.rewardRate.toNumber(), 5);
+		assert.equal(cropData.pendingTimestamp.toNumber(), later);
+		try {
+			await program.rpc.ripen({ accounts: { manager: managerA.publicKey, crop } });
+			assert.fail("applied an update before it was effective");
+		} catch (err) {
+			assert.equal(err.msg, "The pending update is not effective yet");
+		}
+
+		await program.rpc.prune({
+			accounts: {
+				manager: managerA.publicKey,
+				crop,
+				operator: payer.publicKey
+			}
+		});
+		cropData = await program.account.crop.fetch(crop);
+		assert.equal(cropData.pendingTimestamp.toNumber(), 0);
+		assert.equal(cropData.rewardRate.toNumber(), 0);
+	});
+
 	it("sows", async () => {
 		await sow(managerA.publicKey, payer.publicKey, 1, 0);
 		await uproot(managerA.publicKey, payer.publicKey, 1, 0);
 	});
 
+	it("appraises pending rewards", async () => {
+		const [crop, ] = await findCrop(managerA.publicKey, 0);
+		const [plot, ] = await findPlot(managerA.publicKey, payer.publicKey, 0);
+		const { events } = await program.simulate.appraise({
+			accounts: {
+				crop,
+				plot,
+				farmer: payer.publicKey
+			}
+		});
+		assert.equal(events[0].name, "PendingRewards");
+		assert.ok(events[0].data.plot.equals(plot));
+		assert.equal(events[0].data.rewards.toNumber(), 0);
+	});
+
+	it("issues receipts", async () => {
+		const [crop, ] = await findCrop(managerA.publicKey, 3);
+		await cultivateCrop(managerA, depositMintA, rewardMintA, 3);
+		await tillPlot(managerA.publicKey, payer.publicKey, 3);
+
+		const [receiptMint, seed] = await findReceiptMint(crop);
+		await program.rpc.certify(seed, {
+			accounts: {
+				manager: managerA.publicKey,
+				crop,
+				receiptMint,
+				depositMint: depositMintA.publicKey,
+				operator: payer.publicKey,
+				tokenProgram: TOKEN_PROGRAM_ID,
+				systemProgram: SystemProgram.programId,
+				rent: SYSVAR_RENT_PUBKEY
+			}
+		});
+
+		const receipts = new Token(connection, receiptMint, TOKEN_PROGRAM_ID, payer);
+		// Receipts only go to the farmer's associated token account
+		const receiptTokenAccount = await receipts.createAssociatedTokenAccount(payer.publicKey);
+		const remainingAccounts = [
+			{ pubkey: receiptMint, isWritable: true, isSigner: false },
+			{ pubkey: receiptTokenAccount, isWritable: true, isSigner: false },
+		];
+
+		await sow(managerA.publicKey, payer.publicKey, 10, 3, remainingAccounts);
+		let info = await receipts.getAccountInfo(receiptTokenAccount);
+		assert.equal(info.amount.toNumber(), 10);
+
+		const [plot, ] = await findPlot(managerA.publicKey, payer.publicKey, 3);
+		await program.rpc.reckon({
+			accounts: {
+				crop,
+				plot,
+				receiptMint,
+				receiptTokenAccount,
+				farmer: payer.publicKey,
+				caller: payer.publicKey
+			}
+		});
+
+		await uproot(managerA.publicKey, payer.publicKey, 10, 3, remainingAccounts);
+		info = await receipts.getAccountInfo(receiptTokenAccount);
+		assert.equal(info.amount.toNumber(), 0);
+	});
+
+	it("grants and revokes roles", async () => {
+		const pauser = Keypair.generate();
+		const [crop, ] = await findCrop(managerA.publicKey, 2);
+
+		await program.rpc.grant({ pauser: {} }, pauser.publicKey, {
+			accounts: {
+				manager: managerA.publicKey,
+				owner: payer.publicKey
+			}
+		});
+
+		await program.rpc.fallow(true, {
+			accounts: {
+				manager: managerA.publicKey,
+				crop,
+				pauser: pauser.publicKey
+			},
+			signers: [pauser]
+		});
+		let cropData = await program.account.crop.fetch(crop);
+		assert.ok(cropData.paused);
+
+		await program.rpc.revoke({ pauser: {} }, {
+			accounts: {
+				manager: managerA.publicKey,
+				owner: payer.publicKey
+			}
+		});
+
+		// The owner always holds every role
+		await program.rpc.fallow(false, {
+			accounts: {
+				manager: managerA.publicKey,
+				crop,
+				pauser: payer.publicKey
+			}
+		});
+		cropData = await program.account.crop.fetch(crop);
+		assert.ok(!cropData.paused);
+	});
+
+	it("attributes referrals", async () => {
+		const farmer = Keypair.generate();
+		await connection.confirmTransaction(
+			await connection.requestAirdrop(farmer.publicKey, anchor.web3.LAMPORTS_PER_SOL)
+		);
+
+		const [crop, ] = await findCrop(managerA.publicKey, 1);
+		const [referral, seed] = await findReferral(crop, payer.publicKey);
+		await program.rpc.enlist(seed, {
+			accounts: {
+				crop,
+				referral,
+				referrer: payer.publicKey,
+				systemProgram: SystemProgram.programId,
+				rent: SYSVAR_RENT_PUBKEY
+			}
+		});
+
+		await tillPlot(managerA.publicKey, farmer.publicKey, 1, [farmer]);
+		await sow(
+			managerA.publicKey, farmer.publicKey, 10, 1,
+			[{ pubkey: referral, isWritable: true, isSigner: false }],
+			payer.publicKey, [farmer]
+		);
+
+		const [plot, ] = await findPlot(managerA.publicKey, farmer.publicKey, 1);
+		const plotData = await program.account.plot.fetch(plot);
+		assert.ok(plotData.referrer.equals(payer.publicKey));
+		const referralData = await program.account.referral.fetch(referral);
+		assert.equal(referralData.referred.toNumber(), 1);
+	});
+
+	it("labels crops when cultivated", async () => {
+		const [crop, ] = await findCrop(managerA.publicKey, 4);
+		const [metadata, ] = await findMetadata(crop);
+		await cultivateCrop(
+			managerA, depositMintA, rewardMintA, 4,
+			{ name: "Crop A", uri: "https://example.com/crop-a.json", tags: ["stable"] },
+			[{ pubkey: metadata, isWritable: true, isSigner: false }]
+		);
+
+		const metadataData = await program.account.metadata.fetch(metadata);
+		assert.ok(metadataData.crop.equals(crop));
+		assert.equal(metadataData.name, "Crop A");
+		assert.equal(metadataData.uri, "https://example.com/crop-a.json");
+		assert.deepEqual(metadataData.tags, ["stable"]);
+	});
+
 	// it("Can transfer ownership", async () => {
 	// 	const [authority, ] = await findAuthority();
 	// 	const tx = await program.rpc.transferOwnership(payer.publicKey, {